@@ -0,0 +1,83 @@
+//! Ways to resolve pixels outside of an image.
+
+/// A way to resolve pixels outside of an image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Border<T> {
+    /// Pixels outside of the image have this constant value. `iiiiii|abcdefgh|iiiiiii`
+    Constant(T),
+    /// Pixels outside of the image have the value of the nearest edge pixel. `aaaaaa|abcdefgh|hhhhhhh`
+    Clamp,
+    /// The image is mirrored at its edges, including the edge pixel. `fedcba|abcdefgh|hgfedcb`
+    Reflect,
+    /// The image is mirrored at its edges, excluding the edge pixel. `gfedcb|abcdefgh|gfedcba`
+    Reflect101,
+    /// The image is repeated. `cdefgh|abcdefgh|abcdefg`
+    Wrap,
+}
+
+impl<T> Border<T> {
+    /// Map a position on an axis of length `length` into `0..length`.
+    /// Returns None if the position is outside of the axis and this is Border::Constant, or the axis is empty.
+    pub fn map(&self, position: isize, length: usize) -> Option<usize> {
+        let len = length as isize;
+        if (0..len).contains(&position) {
+            return Some(position as usize);
+        }
+        if length == 0 {
+            return None;
+        }
+        let position = match self {
+            Border::Constant(_) => return None,
+            Border::Clamp => position.clamp(0, len - 1),
+            Border::Reflect => {
+                let position = position.rem_euclid(2 * len);
+                if position < len {
+                    position
+                } else {
+                    2 * len - 1 - position
+                }
+            }
+            Border::Reflect101 => {
+                if length == 1 {
+                    0
+                } else {
+                    let position = position.rem_euclid(2 * len - 2);
+                    if position < len {
+                        position
+                    } else {
+                        2 * len - 2 - position
+                    }
+                }
+            }
+            Border::Wrap => position.rem_euclid(len),
+        };
+        Some(position as usize)
+    }
+
+    /// Get the constant value if this is Border::Constant.
+    pub fn constant(&self) -> Option<&T> {
+        match self {
+            Border::Constant(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::border::Border;
+
+    #[test]
+    fn map() {
+        let map = |border: &Border<()>, length: usize| (-6..14).map(|i| border.map(i, length)).collect::<Vec<_>>();
+        let some = |v: &[usize]| v.iter().copied().map(Some).collect::<Vec<_>>();
+        assert_eq!(map(&Border::Constant(()), 8), [vec![None; 6], some(&[0, 1, 2, 3, 4, 5, 6, 7]), vec![None; 6]].concat());
+        assert_eq!(map(&Border::Clamp, 8), some(&[0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 7, 7, 7, 7, 7, 7]));
+        assert_eq!(map(&Border::Reflect, 8), some(&[5, 4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 7, 6, 5, 4, 3, 2]));
+        assert_eq!(map(&Border::Reflect101, 8), some(&[6, 5, 4, 3, 2, 1, 0, 1, 2, 3, 4, 5, 6, 7, 6, 5, 4, 3, 2, 1]));
+        assert_eq!(map(&Border::Wrap, 8), some(&[2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5]));
+        assert_eq!(map(&Border::Reflect, 2), some(&[1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1, 1, 0, 0, 1]));
+        assert_eq!(map(&Border::Reflect101, 1), vec![Some(0); 20]);
+        assert_eq!(map(&Border::Clamp, 0), vec![None; 20]);
+    }
+}
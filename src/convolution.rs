@@ -0,0 +1,551 @@
+//! Correlation and convolution with kernels.
+
+use image::{Bgr, Bgra, Luma, LumaA, Primitive, Rgb, Rgba};
+use partial_const::MayBeConst;
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator, ParallelSliceMut};
+
+use crate::border::Border;
+use crate::physical_image::PhysicalImage;
+use crate::ReadPixel;
+
+/// A trait for pixel types which can be weighted and summed up by convolution.
+pub trait Convolve: Copy + Send + Sync {
+    /// A type holding weighted sum of pixels.
+    type Accumulator: Convolve<Accumulator = Self::Accumulator>;
+    /// Get accumulator which has no value.
+    fn zero() -> Self::Accumulator;
+    /// Add this value multiplied by weight to accumulator.
+    fn mul_add(self, weight: f32, accumulator: Self::Accumulator) -> Self::Accumulator;
+    /// Convert accumulated value into pixel.
+    fn from_accumulator(accumulator: Self::Accumulator) -> Self;
+}
+
+impl Convolve for f32 {
+    type Accumulator = f32;
+
+    fn zero() -> f32 {
+        0.0
+    }
+
+    fn mul_add(self, weight: f32, accumulator: f32) -> f32 {
+        accumulator + self * weight
+    }
+
+    fn from_accumulator(accumulator: f32) -> Self {
+        accumulator
+    }
+}
+
+impl Convolve for f64 {
+    type Accumulator = f64;
+
+    fn zero() -> f64 {
+        0.0
+    }
+
+    fn mul_add(self, weight: f32, accumulator: f64) -> f64 {
+        accumulator + self * weight as f64
+    }
+
+    fn from_accumulator(accumulator: f64) -> Self {
+        accumulator
+    }
+}
+
+macro_rules! impl_convolve_integer {
+    ($($t:ty => $accumulator:ty;)*) => {
+        $(
+            impl Convolve for $t {
+                type Accumulator = $accumulator;
+
+                fn zero() -> $accumulator {
+                    0.0
+                }
+
+                fn mul_add(self, weight: f32, accumulator: $accumulator) -> $accumulator {
+                    accumulator + self as $accumulator * weight as $accumulator
+                }
+
+                fn from_accumulator(accumulator: $accumulator) -> Self {
+                    accumulator.round() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_convolve_integer! {
+    u8 => f32;
+    i8 => f32;
+    u16 => f32;
+    i16 => f32;
+    u32 => f64;
+    i32 => f64;
+}
+
+impl<const N: usize> Convolve for [f32; N] {
+    type Accumulator = [f32; N];
+
+    fn zero() -> [f32; N] {
+        [0.0; N]
+    }
+
+    fn mul_add(self, weight: f32, mut accumulator: [f32; N]) -> [f32; N] {
+        accumulator.iter_mut().zip(self.iter()).for_each(|(accumulator, value)| *accumulator += value * weight);
+        accumulator
+    }
+
+    fn from_accumulator(accumulator: [f32; N]) -> Self {
+        accumulator
+    }
+}
+
+macro_rules! impl_convolve_pixel {
+    ($($pixel:ident, $channels:expr;)*) => {
+        $(
+            impl<S: Primitive + Convolve<Accumulator = f32>> Convolve for $pixel<S> {
+                type Accumulator = [f32; $channels];
+
+                fn zero() -> [f32; $channels] {
+                    [0.0; $channels]
+                }
+
+                fn mul_add(self, weight: f32, mut accumulator: [f32; $channels]) -> [f32; $channels] {
+                    accumulator.iter_mut().zip(self.0.iter()).for_each(|(accumulator, value)| *accumulator = value.mul_add(weight, *accumulator));
+                    accumulator
+                }
+
+                fn from_accumulator(accumulator: [f32; $channels]) -> Self {
+                    let mut pixel = [S::from_accumulator(0.0); $channels];
+                    pixel.iter_mut().zip(accumulator.iter()).for_each(|(pixel, accumulator)| *pixel = S::from_accumulator(*accumulator));
+                    $pixel(pixel)
+                }
+            }
+        )*
+    };
+}
+
+impl_convolve_pixel! {
+    Luma, 1;
+    LumaA, 2;
+    Rgb, 3;
+    Rgba, 4;
+    Bgr, 3;
+    Bgra, 4;
+}
+
+/// Resolve positions around the valid area of a source image by the border.
+pub(crate) struct Resolver<'a, S: ReadPixel> {
+    source: &'a S,
+    border: &'a Border<S::Item>,
+    valid_x: usize,
+    pub(crate) valid_y: usize,
+    valid_width: usize,
+    pub(crate) valid_height: usize,
+}
+
+impl<'a, S: ReadPixel> Resolver<'a, S> {
+    pub(crate) fn new(source: &'a S, border: &'a Border<S::Item>) -> Self {
+        let rect = source.valid_rect();
+        Resolver {
+            source,
+            border,
+            valid_x: rect.x,
+            valid_y: rect.y,
+            valid_width: rect.w,
+            valid_height: rect.h,
+        }
+    }
+
+    /// Map a column of the source into a valid column, or None for the constant.
+    pub(crate) fn map_x(&self, x: isize) -> Option<usize> {
+        self.border.map(x - self.valid_x as isize, self.valid_width).map(|x| x + self.valid_x)
+    }
+
+    /// Map a row of the source into a valid row, or None for the constant.
+    pub(crate) fn map_y(&self, y: isize) -> Option<usize> {
+        self.border.map(y - self.valid_y as isize, self.valid_height).map(|y| y + self.valid_y)
+    }
+
+    pub(crate) fn get(&self, x: Option<usize>, y: Option<usize>) -> S::Item
+    where
+        S::Item: Copy,
+    {
+        match (x, y) {
+            (Some(x), Some(y)) => unsafe { *self.source.get_unchecked(x, y) },
+            _ => *self.border.constant().expect("an empty image cannot be extended by this border"),
+        }
+    }
+
+    /// Range of x in which pixels from x - anchor to x - anchor + kernel_width are all valid.
+    fn interior_x(&self, kernel_width: usize, anchor: usize, width: usize) -> (usize, usize) {
+        interior(self.valid_x, self.valid_width, kernel_width, anchor, width)
+    }
+
+    /// Range of y in which pixels from y - anchor to y - anchor + kernel_height are all valid.
+    fn interior_y(&self, kernel_height: usize, anchor: usize, height: usize) -> (usize, usize) {
+        interior(self.valid_y, self.valid_height, kernel_height, anchor, height)
+    }
+}
+
+fn interior(valid_begin: usize, valid_length: usize, kernel_length: usize, anchor: usize, length: usize) -> (usize, usize) {
+    let begin = (valid_begin + anchor).min(length);
+    let end = (valid_begin + valid_length + anchor + 1).saturating_sub(kernel_length).min(length);
+    (begin, end.max(begin))
+}
+
+pub(crate) fn par_rows<T: Send, F: Fn(usize, &mut [T]) + Sync + Send>(width: usize, height: usize, f: F) -> PhysicalImage<T> {
+    let mut image = unsafe { PhysicalImage::new_uninit(width, height) };
+    if width > 0 {
+        image.data.par_chunks_mut(width).enumerate().for_each(|(y, row)| f(y, row));
+    }
+    image
+}
+
+/// Find vectors kernel_x and kernel_y which satisfy kernel(x, y) == kernel_x[x] * kernel_y[y].
+/// Returns None if kernel is not separable.
+pub fn separate_kernel<KW: MayBeConst<usize>, KH: MayBeConst<usize>>(kernel: &PhysicalImage<f32, KW, KH>) -> Option<(Vec<f32>, Vec<f32>)> {
+    let width = kernel.width();
+    let data = kernel.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>();
+    let (index, max) = data.iter().copied().enumerate().max_by(|(_, a), (_, b)| a.abs().partial_cmp(&b.abs()).unwrap())?;
+    if max == 0.0 {
+        return Some((vec![0.0; width], vec![0.0; kernel.height()]));
+    }
+    let (max_x, max_y) = (index % width, index / width);
+    let kernel_x = data[max_y * width..(max_y + 1) * width].to_vec();
+    let kernel_y = data.iter().skip(max_x).step_by(width).map(|value| value / max).collect::<Vec<_>>();
+    let tolerance = max.abs() * 1e-5;
+    let separable = data.iter().enumerate().all(|(i, value)| (value - kernel_x[i % width] * kernel_y[i / width]).abs() <= tolerance);
+    if separable {
+        Some((kernel_x, kernel_y))
+    } else {
+        None
+    }
+}
+
+/// Correlate source image with kernel.
+/// The output pixel (x, y) is the sum of kernel(i, j) * source(x + i - kernel_width / 2, y + j - kernel_height / 2).
+/// Pixels outside of valid area of source are resolved by border.
+/// Separable kernels are processed as two 1D passes.
+/// # Panics
+/// Panics if kernel is empty, or if border is not Constant and source has pixels but none of them is valid.
+pub fn correlate<S, KW, KH>(source: &S, kernel: &PhysicalImage<f32, KW, KH>, border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    KW: MayBeConst<usize> + Sync,
+    KH: MayBeConst<usize> + Sync,
+{
+    let (kernel_width, kernel_height) = (kernel.width(), kernel.height());
+    if kernel_width > 1 && kernel_height > 1 {
+        if let Some((kernel_x, kernel_y)) = separate_kernel(kernel) {
+            return correlate_separable_with_anchor(source, &kernel_x, kernel_width / 2, &kernel_y, kernel_height / 2, border);
+        }
+    }
+    correlate_with_anchor(source, kernel, kernel_width / 2, kernel_height / 2, border)
+}
+
+/// Convolve source image with kernel.
+/// This is the same as correlate with the kernel flipped in both directions.
+/// # Panics
+/// Panics if kernel is empty, or if border is not Constant and source has pixels but none of them is valid.
+pub fn convolve<S, KW, KH>(source: &S, kernel: &PhysicalImage<f32, KW, KH>, border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    KW: MayBeConst<usize> + Sync,
+    KH: MayBeConst<usize> + Sync,
+{
+    let (kernel_width, kernel_height) = (kernel.width(), kernel.height());
+    let mut data = kernel.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>();
+    data.reverse();
+    let flipped = PhysicalImage::with_data(kernel.width, kernel.height, data);
+    let (anchor_x, anchor_y) = (kernel_width.saturating_sub(1) - kernel_width / 2, kernel_height.saturating_sub(1) - kernel_height / 2);
+    if kernel_width > 1 && kernel_height > 1 {
+        if let Some((kernel_x, kernel_y)) = separate_kernel(&flipped) {
+            return correlate_separable_with_anchor(source, &kernel_x, anchor_x, &kernel_y, anchor_y, border);
+        }
+    }
+    correlate_with_anchor(source, &flipped, anchor_x, anchor_y, border)
+}
+
+/// Correlate source image with separable kernel kernel_x[i] * kernel_y[j] as two 1D passes.
+/// # Panics
+/// Panics if kernel is empty, or if border is not Constant and source has pixels but none of them is valid.
+pub fn correlate_separable<S>(source: &S, kernel_x: &[f32], kernel_y: &[f32], border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+{
+    correlate_separable_with_anchor(source, kernel_x, kernel_x.len() / 2, kernel_y, kernel_y.len() / 2, border)
+}
+
+/// Convolve source image with separable kernel kernel_x[i] * kernel_y[j] as two 1D passes.
+/// # Panics
+/// Panics if kernel is empty, or if border is not Constant and source has pixels but none of them is valid.
+pub fn convolve_separable<S>(source: &S, kernel_x: &[f32], kernel_y: &[f32], border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+{
+    let flipped_x = kernel_x.iter().rev().copied().collect::<Vec<_>>();
+    let flipped_y = kernel_y.iter().rev().copied().collect::<Vec<_>>();
+    let anchor_x = kernel_x.len().saturating_sub(1) - kernel_x.len() / 2;
+    let anchor_y = kernel_y.len().saturating_sub(1) - kernel_y.len() / 2;
+    correlate_separable_with_anchor(source, &flipped_x, anchor_x, &flipped_y, anchor_y, border)
+}
+
+fn correlate_with_anchor<S, KW, KH>(source: &S, kernel: &PhysicalImage<f32, KW, KH>, anchor_x: usize, anchor_y: usize, border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    KW: MayBeConst<usize> + Sync,
+    KH: MayBeConst<usize> + Sync,
+{
+    let (width, height) = (source.width(), source.height());
+    let (kernel_width, kernel_height) = (kernel.width, kernel.height);
+    assert!(kernel_width.value() > 0 && kernel_height.value() > 0, "kernel is empty");
+    let resolver = Resolver::new(source, border);
+    let (interior_begin_x, interior_end_x) = resolver.interior_x(kernel_width.value(), anchor_x, width);
+    let (interior_begin_y, interior_end_y) = resolver.interior_y(kernel_height.value(), anchor_y, height);
+    let columns = (0..width + kernel_width.value()).map(|x| resolver.map_x(x as isize - anchor_x as isize)).collect::<Vec<_>>();
+    par_rows(width, height, |y, row| {
+        let rows = (0..kernel_height.value()).map(|j| resolver.map_y((y + j) as isize - anchor_y as isize)).collect::<Vec<_>>();
+        let resolved = |x: usize| {
+            let mut accumulator = S::Item::zero();
+            for (j, &source_y) in rows.iter().enumerate() {
+                for i in 0..kernel_width.value() {
+                    let weight = unsafe { *kernel.get_unchecked(i, j) };
+                    accumulator = resolver.get(columns[x + i], source_y).mul_add(weight, accumulator);
+                }
+            }
+            S::Item::from_accumulator(accumulator)
+        };
+        let (begin, end) = if (interior_begin_y..interior_end_y).contains(&y) {
+            (interior_begin_x, interior_end_x)
+        } else {
+            (width, width)
+        };
+        for (x, value) in row.iter_mut().enumerate().take(begin) {
+            let value: *mut S::Item = value;
+            unsafe { value.write(resolved(x)) };
+        }
+        for (x, value) in row.iter_mut().enumerate().take(end).skip(begin) {
+            let mut accumulator = S::Item::zero();
+            for j in 0..kernel_height.value() {
+                for i in 0..kernel_width.value() {
+                    let weight = unsafe { *kernel.get_unchecked(i, j) };
+                    let pixel = unsafe { *source.get_unchecked(x + i - anchor_x, y + j - anchor_y) };
+                    accumulator = pixel.mul_add(weight, accumulator);
+                }
+            }
+            let value: *mut S::Item = value;
+            unsafe { value.write(S::Item::from_accumulator(accumulator)) };
+        }
+        for (x, value) in row.iter_mut().enumerate().skip(end.max(begin)) {
+            let value: *mut S::Item = value;
+            unsafe { value.write(resolved(x)) };
+        }
+    })
+}
+
+fn correlate_separable_with_anchor<S>(source: &S, kernel_x: &[f32], anchor_x: usize, kernel_y: &[f32], anchor_y: usize, border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+{
+    assert!(!kernel_x.is_empty() && !kernel_y.is_empty(), "kernel is empty");
+    let (width, height) = (source.width(), source.height());
+    let resolver = Resolver::new(source, border);
+    let (interior_begin, interior_end) = resolver.interior_x(kernel_x.len(), anchor_x, width);
+    let columns = (0..width + kernel_x.len()).map(|x| resolver.map_x(x as isize - anchor_x as isize)).collect::<Vec<_>>();
+    let horizontal = par_rows(width, resolver.valid_height, |row_index, row| {
+        let y = Some(resolver.valid_y + row_index);
+        let resolved = |x: usize| {
+            kernel_x
+                .iter()
+                .enumerate()
+                .fold(S::Item::zero(), |accumulator, (i, &weight)| resolver.get(columns[x + i], y).mul_add(weight, accumulator))
+        };
+        for (x, value) in row.iter_mut().enumerate() {
+            let accumulator = if (interior_begin..interior_end).contains(&x) {
+                kernel_x.iter().enumerate().fold(S::Item::zero(), |accumulator, (i, &weight)| {
+                    unsafe { *source.get_unchecked(x + i - anchor_x, resolver.valid_y + row_index) }.mul_add(weight, accumulator)
+                })
+            } else {
+                resolved(x)
+            };
+            let value: *mut <S::Item as Convolve>::Accumulator = value;
+            unsafe { value.write(accumulator) };
+        }
+    });
+    let constant_row = border
+        .constant()
+        .map(|&constant| kernel_x.iter().fold(S::Item::zero(), |accumulator, &weight| constant.mul_add(weight, accumulator)));
+    let (interior_begin, interior_end) = resolver.interior_y(kernel_y.len(), anchor_y, height);
+    par_rows(width, height, |y, row| {
+        let rows = (0..kernel_y.len()).map(|j| resolver.map_y((y + j) as isize - anchor_y as isize)).collect::<Vec<_>>();
+        let interior = (interior_begin..interior_end).contains(&y);
+        for (x, value) in row.iter_mut().enumerate() {
+            let accumulator = kernel_y.iter().zip(rows.iter()).fold(S::Item::zero(), |accumulator, (&weight, &source_y)| {
+                let pixel = match source_y {
+                    Some(source_y) if interior => unsafe { *horizontal.data.get_unchecked((source_y - resolver.valid_y) * width + x) },
+                    Some(source_y) => horizontal.data[(source_y - resolver.valid_y) * width + x],
+                    None => constant_row.expect("an empty image cannot be extended by this border"),
+                };
+                pixel.mul_add(weight, accumulator)
+            });
+            let value: *mut S::Item = value;
+            unsafe { value.write(S::Item::from_accumulator(accumulator)) };
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+    use partial_const::ConstUsize;
+
+    use crate::border::Border;
+    use crate::convolution::{convolve, convolve_separable, correlate, correlate_separable, separate_kernel};
+    use crate::physical_image::PhysicalImage;
+    use crate::{ReadPixel, View, WritePixel};
+
+    fn naive_correlate(source: &PhysicalImage<f32>, kernel: &PhysicalImage<f32>, anchor_x: usize, anchor_y: usize, border: &Border<f32>) -> PhysicalImage<f32> {
+        let mut result = PhysicalImage::new(source.width(), source.height());
+        for y in 0..source.height() {
+            for x in 0..source.width() {
+                let mut sum = 0.0;
+                for j in 0..kernel.height() {
+                    for i in 0..kernel.width() {
+                        let source_x = border.map(x as isize + i as isize - anchor_x as isize, source.width());
+                        let source_y = border.map(y as isize + j as isize - anchor_y as isize, source.height());
+                        let value = match (source_x, source_y) {
+                            (Some(x), Some(y)) => *source.get(x, y).unwrap(),
+                            _ => *border.constant().unwrap(),
+                        };
+                        sum += kernel.get(i, j).unwrap() * value;
+                    }
+                }
+                *result.get_mut(x, y).unwrap() = sum;
+            }
+        }
+        result
+    }
+
+    fn assert_close(a: &PhysicalImage<f32>, b: &PhysicalImage<f32>) {
+        assert_eq!(a.width(), b.width());
+        assert_eq!(a.height(), b.height());
+        for (a, b) in a.data.iter().zip(b.data.iter()) {
+            assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+        }
+    }
+
+    fn test_image(width: usize, height: usize) -> PhysicalImage<f32> {
+        let mut image = PhysicalImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                *image.get_mut(x, y).unwrap() = ((x * 7 + y * 13) % 17) as f32;
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn correlate_kernel() {
+        let image = test_image(23, 17);
+        let mut kernel = PhysicalImage::new(4, 3);
+        for (i, value) in kernel.data.iter_mut().enumerate() {
+            *value = (i as f32 * 0.37).sin();
+        }
+        assert!(separate_kernel(&kernel).is_none());
+        let flipped = PhysicalImage::with_data(4, 3, kernel.data.iter().rev().copied().collect());
+        for border in [Border::Constant(1.5), Border::Clamp, Border::Reflect, Border::Reflect101, Border::Wrap].iter() {
+            assert_close(&correlate(&image, &kernel, border), &naive_correlate(&image, &kernel, 2, 1, border));
+            assert_close(&convolve(&image, &kernel, border), &naive_correlate(&image, &flipped, 1, 1, border));
+        }
+        let small = test_image(2, 1);
+        assert_close(&correlate(&small, &kernel, &Border::Reflect), &naive_correlate(&small, &kernel, 2, 1, &Border::Reflect));
+    }
+
+    #[test]
+    fn correlate_separable_kernel() {
+        let image = test_image(23, 17);
+        let kernel_x = [1.0, 4.0, 6.0, 4.0, 1.0];
+        let kernel_y = [-1.0, 0.0, 2.0];
+        let mut kernel = PhysicalImage::new(ConstUsize::<5>::new(), ConstUsize::<3>::new());
+        for (y, weight_y) in kernel_y.iter().enumerate() {
+            for (x, weight_x) in kernel_x.iter().enumerate() {
+                *kernel.get_mut(x, y).unwrap() = weight_x * weight_y;
+            }
+        }
+        let (separated_x, separated_y) = separate_kernel(&kernel).unwrap();
+        for (y, weight_y) in separated_y.iter().enumerate() {
+            for (x, weight_x) in separated_x.iter().enumerate() {
+                assert!((weight_x * weight_y - kernel.get(x, y).unwrap()).abs() < 1e-5);
+            }
+        }
+        let dynamic = PhysicalImage::with_data(5, 3, kernel.data.clone());
+        let flipped = PhysicalImage::with_data(5, 3, kernel.data.iter().rev().copied().collect());
+        for border in [Border::Constant(-2.0), Border::Clamp, Border::Reflect, Border::Reflect101, Border::Wrap].iter() {
+            let expect = naive_correlate(&image, &dynamic, 2, 1, border);
+            assert_close(&correlate(&image, &kernel, border), &expect);
+            assert_close(&correlate_separable(&image, &kernel_x, &kernel_y, border), &expect);
+            let expect = naive_correlate(&image, &flipped, 2, 1, border);
+            assert_close(&convolve(&image, &kernel, border), &expect);
+            assert_close(&convolve_separable(&image, &kernel_x, &kernel_y, border), &expect);
+        }
+    }
+
+    #[test]
+    fn correlate_view() {
+        let image = test_image(23, 17);
+        let view = image.view(3, 2, 10, 12).unwrap();
+        let copied = PhysicalImage::with_data(10, 12, view.pix_iter_serialized().into_inner().copied().collect());
+        let kernel = PhysicalImage::with_data(3, 3, vec![1.0, 2.0, 1.0, 2.0, 4.0, 2.0, 1.0, 2.0, 1.0]);
+        assert_close(&correlate(&view, &kernel, &Border::Reflect101), &naive_correlate(&copied, &kernel, 1, 1, &Border::Reflect101));
+
+        let overhang = image.view_overhang(-2, -3, 30, 25);
+        let result = correlate(&overhang, &kernel, &Border::Clamp);
+        assert_eq!(result.width(), 30);
+        assert_eq!(result.height(), 25);
+        let expect = naive_correlate(&image, &kernel, 1, 1, &Border::Clamp);
+        for y in 0..17 {
+            for x in 0..23 {
+                assert!((result.get(x + 2, y + 3).unwrap() - expect.get(x, y).unwrap()).abs() < 1e-3);
+            }
+        }
+        assert_eq!(result.get(0, 0), result.get(1, 2));
+
+        let rgb = PhysicalImage::with_default(8, 8, Rgb([10u8, 20, 30]));
+        let blurred = correlate(&rgb, &kernel, &Border::Constant(Rgb([0, 0, 0])));
+        assert_eq!(blurred.get(4, 4), Some(&Rgb([160, 255, 255])));
+        assert_eq!(blurred.get(0, 0), Some(&Rgb([90, 180, 255])));
+
+        let mut aligned_image = PhysicalImage::new_aligned(23, 17, 64);
+        image.pix_iter().map(|v| *v).collect_into(&mut aligned_image);
+        let mut aligned_kernel = PhysicalImage::new_aligned(3, 2, 32);
+        let weights = PhysicalImage::with_data(3, 2, vec![1.0, -2.0, 3.0, 0.5, 1.0, 2.0]);
+        weights.pix_iter().map(|v| *v).collect_into(&mut aligned_kernel);
+        assert_close(&correlate(&aligned_image, &aligned_kernel, &Border::Wrap), &naive_correlate(&image, &weights, 1, 1, &Border::Wrap));
+        assert_close(&convolve(&aligned_image, &aligned_kernel, &Border::Wrap), &convolve(&image, &weights, &Border::Wrap));
+    }
+
+    #[test]
+    #[should_panic(expected = "kernel is empty")]
+    fn convolve_empty_kernel() {
+        convolve(&PhysicalImage::<f32>::new(3, 3), &PhysicalImage::<f32>::new(0, 3), &Border::Clamp);
+    }
+
+    #[test]
+    #[should_panic(expected = "kernel is empty")]
+    fn convolve_separable_empty_kernel() {
+        convolve_separable(&PhysicalImage::<f32>::new(3, 3), &[1.0], &[], &Border::Clamp);
+    }
+
+    #[test]
+    #[should_panic(expected = "an empty image cannot be extended")]
+    fn correlate_empty_source() {
+        correlate(&PhysicalImage::<f32>::new(3, 3).view_overhang(5, 0, 2, 2), &PhysicalImage::with_default(3, 3, 1.0), &Border::Clamp);
+    }
+}
@@ -0,0 +1,357 @@
+//! Histograms of channels, equalization and histogram matching.
+
+use image::{Bgr, Bgra, Luma, LumaA, Primitive, Rgb, Rgba};
+use partial_const::MayBeConst;
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
+
+use crate::physical_image::PhysicalImage;
+use crate::pixel_iter::PixIter;
+use crate::{ChannelPixel, View};
+
+/// A trait for pixel types whose channels can be counted in histograms.
+pub trait HistogramPixel: ChannelPixel {
+    /// A pixel type made by map_channels.
+    type Pixel;
+    /// Number of bins used by equalize and match_histogram.
+    const DEFAULT_BINS: usize;
+    /// Range of values used by equalize and match_histogram, both ends inclusive.
+    const DEFAULT_RANGE: (f64, f64);
+    /// Make a pixel whose channel i is f(i, channel(i)), rounded and saturated into the channel type.
+    fn map_channels(&self, f: impl FnMut(usize, f64) -> f64) -> Self::Pixel;
+}
+
+macro_rules! impl_histogram_integer {
+    ($($t:ty;)*) => {
+        $(
+            impl HistogramPixel for $t {
+                type Pixel = $t;
+                const DEFAULT_BINS: usize = (<$t>::MAX as i64 - <$t>::MIN as i64 + 1) as usize;
+                const DEFAULT_RANGE: (f64, f64) = (<$t>::MIN as f64, <$t>::MAX as f64);
+
+                fn map_channels(&self, mut f: impl FnMut(usize, f64) -> f64) -> $t {
+                    f(0, *self as f64).round().clamp(<$t>::MIN as f64, <$t>::MAX as f64) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_histogram_integer! {
+    u8;
+    i8;
+    u16;
+    i16;
+}
+
+macro_rules! impl_histogram_float {
+    ($($t:ty;)*) => {
+        $(
+            impl HistogramPixel for $t {
+                type Pixel = $t;
+                const DEFAULT_BINS: usize = 256;
+                const DEFAULT_RANGE: (f64, f64) = (0.0, 1.0);
+
+                fn map_channels(&self, mut f: impl FnMut(usize, f64) -> f64) -> $t {
+                    f(0, *self as f64) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_histogram_float! {
+    f32;
+    f64;
+}
+
+macro_rules! impl_histogram_pixel {
+    ($($pixel:ident;)*) => {
+        $(
+            impl<S: Primitive + HistogramPixel<Pixel = S>> HistogramPixel for $pixel<S> {
+                type Pixel = $pixel<S>;
+                const DEFAULT_BINS: usize = S::DEFAULT_BINS;
+                const DEFAULT_RANGE: (f64, f64) = S::DEFAULT_RANGE;
+
+                fn map_channels(&self, mut f: impl FnMut(usize, f64) -> f64) -> $pixel<S> {
+                    let mut pixel = *self;
+                    pixel.0.iter_mut().enumerate().for_each(|(i, value)| *value = value.map_channels(|_, v| f(i, v)));
+                    pixel
+                }
+            }
+        )*
+    };
+}
+
+impl_histogram_pixel! {
+    Luma;
+    LumaA;
+    Rgb;
+    Rgba;
+    Bgr;
+    Bgra;
+}
+
+impl<T: HistogramPixel> HistogramPixel for &T {
+    type Pixel = T::Pixel;
+    const DEFAULT_BINS: usize = T::DEFAULT_BINS;
+    const DEFAULT_RANGE: (f64, f64) = T::DEFAULT_RANGE;
+
+    fn map_channels(&self, f: impl FnMut(usize, f64) -> f64) -> T::Pixel {
+        (**self).map_channels(f)
+    }
+}
+
+/// Counts of values in bins of equal width dividing range [lower, upper].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    lower: f64,
+    upper: f64,
+}
+
+impl Histogram {
+    /// Create empty histogram.
+    /// # Panics
+    /// Panics if bins is 0 or lower > upper.
+    pub fn new(bins: usize, lower: f64, upper: f64) -> Self {
+        assert!(bins > 0, "histogram must have at least one bin");
+        assert!(lower <= upper, "range [{}, {}] of histogram is empty", lower, upper);
+        Histogram { counts: vec![0; bins], lower, upper }
+    }
+
+    /// Get counts of each bin.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Get number of bins.
+    pub fn bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Get range [lower, upper] of this histogram.
+    pub fn range(&self) -> (f64, f64) {
+        (self.lower, self.upper)
+    }
+
+    /// Get sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Get cumulative counts, whose element i is sum of counts of bins 0..=i.
+    pub fn cumulative(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .scan(0, |sum, count| {
+                *sum += count;
+                Some(*sum)
+            })
+            .collect()
+    }
+
+    /// Get the bin containing value. Returns None if value is outside of the range or NaN.
+    pub fn bin(&self, value: f64) -> Option<usize> {
+        if !(self.lower..=self.upper).contains(&value) {
+            return None;
+        }
+        if self.upper == self.lower {
+            return Some(0);
+        }
+        Some((((value - self.lower) / (self.upper - self.lower) * self.bins() as f64) as usize).min(self.bins() - 1))
+    }
+
+    /// Get the representative value of bin, which is lower for bin 0 and upper for the last bin.
+    pub fn bin_value(&self, bin: usize) -> f64 {
+        if self.bins() == 1 {
+            self.lower
+        } else {
+            self.lower + (self.upper - self.lower) * bin as f64 / (self.bins() - 1) as f64
+        }
+    }
+
+    /// Count value. Values outside of the range are ignored.
+    pub fn insert(&mut self, value: f64) {
+        if let Some(bin) = self.bin(value) {
+            self.counts[bin] += 1;
+        }
+    }
+
+    /// Add counts of other histogram with same bins and range.
+    /// # Panics
+    /// Panics if bins or ranges differ.
+    pub fn merge(&mut self, other: &Histogram) {
+        assert_eq!((self.bins(), self.range()), (other.bins(), other.range()), "histograms with different bins cannot be merged");
+        self.counts.iter_mut().zip(other.counts.iter()).for_each(|(a, b)| *a += b);
+    }
+}
+
+/// Compute histograms of each channel of pixels in parallel.
+pub fn histogram<I, W, H>(iter: PixIter<I, W, H>, bins: usize, lower: f64, upper: f64) -> Vec<Histogram>
+where
+    I: ParallelIterator + IndexedParallelIterator,
+    I::Item: HistogramPixel,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let empty = Histogram::new(bins, lower, upper);
+    let empty = || vec![empty.clone(); I::Item::CHANNELS];
+    iter.into_inner()
+        .fold(empty, |mut histograms, pixel| {
+            histograms.iter_mut().enumerate().for_each(|(i, histogram)| histogram.insert(pixel.channel(i)));
+            histograms
+        })
+        .reduce(empty, |mut a, b| {
+            a.iter_mut().zip(b.iter()).for_each(|(a, b)| a.merge(b));
+            a
+        })
+}
+
+fn default_histogram<S>(image: &S) -> Vec<Histogram>
+where
+    S: View,
+    S::Item: HistogramPixel + Sync,
+{
+    let view = image.view(0, 0, image.width(), image.height()).expect("all pixels of image must be valid");
+    let (lower, upper) = S::Item::DEFAULT_RANGE;
+    histogram(view.pix_iter(), S::Item::DEFAULT_BINS, lower, upper)
+}
+
+fn apply_lookup<S>(image: &S, histograms: &[Histogram], lookups: &[Vec<f64>]) -> PhysicalImage<<S::Item as HistogramPixel>::Pixel>
+where
+    S: View,
+    S::Item: HistogramPixel + Sync,
+    <S::Item as HistogramPixel>::Pixel: Send,
+{
+    let view = image.view(0, 0, image.width(), image.height()).expect("all pixels of image must be valid");
+    view.pix_iter()
+        .map(|pixel| pixel.map_channels(|i, value| histograms[i].bin(value).map_or(value, |bin| lookups[i][bin])))
+        .collect_image()
+}
+
+/// Equalize histogram of each channel of image, using DEFAULT_BINS and DEFAULT_RANGE of the pixel type.
+/// # Panics
+/// Panics if any pixel of image is not valid.
+pub fn equalize<S>(image: &S) -> PhysicalImage<<S::Item as HistogramPixel>::Pixel>
+where
+    S: View,
+    S::Item: HistogramPixel + Sync,
+    <S::Item as HistogramPixel>::Pixel: Send,
+{
+    let histograms = default_histogram(image);
+    let lookups = histograms
+        .iter()
+        .map(|histogram| {
+            let cumulative = histogram.cumulative();
+            let total = histogram.total();
+            let first = cumulative.iter().copied().find(|&count| count > 0).unwrap_or(0);
+            let (lower, upper) = histogram.range();
+            (0..histogram.bins())
+                .map(|bin| {
+                    if total == first {
+                        histogram.bin_value(bin)
+                    } else {
+                        lower + (upper - lower) * cumulative[bin].saturating_sub(first) as f64 / (total - first) as f64
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    apply_lookup(image, &histograms, &lookups)
+}
+
+/// Map each channel of image so that its histogram matches that of reference, using DEFAULT_BINS and DEFAULT_RANGE of the pixel type.
+/// # Panics
+/// Panics if any pixel of image or reference is not valid.
+pub fn match_histogram<S, R>(image: &S, reference: &R) -> PhysicalImage<<S::Item as HistogramPixel>::Pixel>
+where
+    S: View,
+    R: View<Item = S::Item>,
+    S::Item: HistogramPixel + Sync,
+    <S::Item as HistogramPixel>::Pixel: Send,
+{
+    let histograms = default_histogram(image);
+    let references = default_histogram(reference);
+    let lookups = histograms
+        .iter()
+        .zip(references.iter())
+        .map(|(histogram, reference)| {
+            let cumulative = histogram.cumulative();
+            let reference_cumulative = reference.cumulative();
+            let (total, reference_total) = (histogram.total().max(1) as f64, reference.total().max(1) as f64);
+            let mut reference_bin = 0;
+            (0..histogram.bins())
+                .map(|bin| {
+                    let quantile = cumulative[bin] as f64 / total;
+                    while reference_bin + 1 < reference.bins() && (reference_cumulative[reference_bin] as f64 / reference_total) < quantile {
+                        reference_bin += 1;
+                    }
+                    reference.bin_value(reference_bin)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    apply_lookup(image, &histograms, &lookups)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use crate::histogram::{equalize, histogram, match_histogram, Histogram};
+    use crate::physical_image::PhysicalImage;
+    use crate::{ReadPixel, View};
+
+    #[test]
+    fn histogram_bins() {
+        let mut bins = Histogram::new(4, 0.0, 1.0);
+        for &value in &[0.0, 0.2, 0.25, 0.5, 0.99, 1.0, 1.01, -0.1, f64::NAN] {
+            bins.insert(value);
+        }
+        assert_eq!(bins.counts(), &[2, 1, 1, 2]);
+        assert_eq!(bins.cumulative(), vec![2, 3, 4, 6]);
+        assert_eq!(bins.total(), 6);
+        assert_eq!((bins.bin_value(0), bins.bin_value(3)), (0.0, 1.0));
+
+        let image = PhysicalImage::from_fn(40, 30, |x, y| Rgb([(x * 5) as u8, (y % 4) as u8, 7u8]));
+        let histograms = histogram(image.pix_iter(), 256, 0.0, 255.0);
+        assert_eq!(histograms.len(), 3);
+        for (i, &value) in histograms[0].counts().iter().enumerate() {
+            assert_eq!(value, if i % 5 == 0 && i < 200 { 30 } else { 0 });
+        }
+        assert_eq!(&histograms[1].counts()[..5], &[320, 320, 280, 280, 0]);
+        assert_eq!(histograms[2].counts()[7], 1200);
+
+        let roi = image.view(10, 5, 4, 3).unwrap();
+        let histograms = histogram(roi.pix_iter(), 4, 0.0, 255.0);
+        assert_eq!(histograms[0].total(), 12);
+        assert_eq!(histograms[0].counts(), &[9, 3, 0, 0]);
+
+        let float = PhysicalImage::from_fn(10, 10, |x, _| x as f32 / 10.0);
+        let histograms = histogram(float.pix_iter(), 5, 0.0, 1.0);
+        assert_eq!(histograms[0].counts(), &[20, 20, 20, 20, 20]);
+    }
+
+    #[test]
+    fn equalize_match() {
+        let image = PhysicalImage::from_fn(64, 16, |x, _| 100 + (x / 8) as u8);
+        let equalized = equalize(&image);
+        let levels = (0..8).map(|i| *equalized.get(i * 8, 0).unwrap()).collect::<Vec<_>>();
+        assert_eq!(levels, vec![0, 36, 73, 109, 146, 182, 219, 255]);
+
+        let constant = PhysicalImage::with_default(4, 4, 42u8);
+        assert_eq!(equalize(&constant).get(1, 1), Some(&42));
+
+        let reference = PhysicalImage::from_fn(64, 16, |x, _| (x / 16 * 50) as u8);
+        let matched = match_histogram(&image, &reference);
+        let levels = (0..8).map(|i| *matched.get(i * 8, 0).unwrap()).collect::<Vec<_>>();
+        assert_eq!(levels, vec![0, 0, 50, 50, 100, 100, 150, 150]);
+        let matched = match_histogram(&image.view(0, 0, 16, 16).unwrap(), &reference);
+        assert_eq!((matched.get(0, 0), matched.get(15, 0)), (Some(&50), Some(&150)));
+
+        let rgb = PhysicalImage::from_fn(16, 4, |x, _| Rgb([x as u8, 200, (x * 2) as u8]));
+        let equalized = equalize(&rgb);
+        assert_eq!(equalized.get(0, 0), Some(&Rgb([0, 200, 0])));
+        assert_eq!(equalized.get(15, 0), Some(&Rgb([255, 200, 255])));
+    }
+}
@@ -1,7 +1,9 @@
 use std::marker::PhantomData;
 
-use partial_const::MayBeConst;
+use partial_const::{ConstUsize, MayBeConst};
+use rayon::prelude::IndexedParallelIterator;
 
+use crate::border::Border;
 use crate::pixel_iter::{PixIter, SerializePixIter};
 use crate::{IntoPixelIterator, IntoSerializedPixelIterator, ReadPixel, Rectangle, View, ViewMut, WritePixel};
 
@@ -33,8 +35,50 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H> {
             lifetime: Default::default(),
         }
     }
+
+    /// Borrow data as an image whose rows start every stride elements.
+    /// If stride < width or data is too short to hold height rows, this function returns None.
+    pub fn from_slice(data: &'a [T], width: W, height: H, stride: usize) -> Option<Self> {
+        if required_len(width.value(), height.value(), stride).map_or(false, |len| len <= data.len()) {
+            Some(ImageRef::new(stride, data.as_ptr(), 0, 0, width, height))
+        } else {
+            None
+        }
+    }
+
+    /// Borrow memory pointed by ptr as an image whose rows start every stride elements.
+    /// # Safety
+    /// stride should be greater than or equal to width, and ptr should be valid for reads of height rows for lifetime 'a.
+    pub unsafe fn from_raw_parts(ptr: *const T, width: W, height: H, stride: usize) -> Self {
+        debug_assert!(stride >= width.value(), "stride {} is less than width {} in ImageRef::from_raw_parts", stride, width);
+        ImageRef::new(stride, ptr, 0, 0, width, height)
+    }
+
+    pub(crate) fn overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<'a, T, RW, RH> {
+        let source = ImageRef::new(self.base_width, self.ptr, self.roi_x, self.roi_y, self.roi_width.value(), self.roi_height.value());
+        ImageRefOverhang::new(source, x, y, w, h)
+    }
+}
+
+/// Number of elements needed to hold an image of given size and stride, or None if stride < width or it overflows.
+fn required_len(width: usize, height: usize, stride: usize) -> Option<usize> {
+    if stride < width {
+        None
+    } else if height == 0 {
+        Some(0)
+    } else {
+        (height - 1).checked_mul(stride)?.checked_add(width)
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> Clone for ImageRef<'a, T, W, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> Copy for ImageRef<'a, T, W, H> {}
+
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRef<'a, T, W, H> {
     type Item = T;
 
@@ -79,21 +123,23 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> View for ImageRef<'a, T,
     }
 
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<T, RW, RH> {
-        let valid_x = x.clamp(0, self.roi_width.value() as isize) as usize;
-        let valid_y = y.clamp(0, self.roi_height.value() as isize) as usize;
-        let valid_width = (x + w.value() as isize).clamp(0, self.roi_width.value() as isize) as usize - valid_x;
-        let valid_height = (y + h.value() as isize).clamp(0, self.roi_height.value() as isize) as usize - valid_y;
-        ImageRefOverhang::new(
-            unsafe { self.view_unchecked(valid_x, valid_y, valid_width, valid_height) },
-            (-x).max(0) as usize,
-            (-y).max(0) as usize,
-            w,
-            h,
-        )
+        self.overhang(x, y, w, h)
     }
 }
 
 impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H> {
+    /// Iterate windows of size (kernel_width, kernel_height) centered on each pixel.
+    /// Pixels of a window outside of this image are not valid in the window.
+    pub fn pix_iter_window<KW: MayBeConst<usize>, KH: MayBeConst<usize>>(&self, kernel_width: KW, kernel_height: KH) -> PixIter<iter::IterWindow<'a, T, KW, KH>, W, H> {
+        let &ImageRef { roi_width, roi_height, .. } = self;
+        let image = ImageRef::new(self.base_width, self.ptr, self.roi_x, self.roi_y, roi_width.value(), roi_height.value());
+        PixIter::new(
+            iter::IterWindow::new(image, kernel_width, kernel_height, 0..roi_width.value() * roi_height.value()),
+            roi_width,
+            roi_height,
+        )
+    }
+
     pub fn pix_iter(&self) -> PixIter<iter::Iter<'a, T>, W, H> {
         let &ImageRef {
             base_width,
@@ -133,6 +179,62 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H
     }
 }
 
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H> {
+    /// Iterate rows of this image as (y, row).
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = (usize, &'a [T])> + DoubleEndedIterator {
+        iter::Rows::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_width.value(), 0..self.roi_height.value())
+    }
+
+    /// Iterate columns of this image as (x, column).
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = (usize, ImageRef<'a, T, ConstUsize<1>, usize>)> + DoubleEndedIterator {
+        iter::Columns::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_height.value(), 0..self.roi_width.value())
+    }
+}
+
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H> {
+    /// Iterate rows of this image as (y, row) in parallel.
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = (usize, &'a [T])> {
+        iter::Rows::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_width.value(), 0..self.roi_height.value())
+    }
+
+    /// Iterate columns of this image as (x, column) in parallel.
+    pub fn par_columns(&self) -> impl IndexedParallelIterator<Item = (usize, ImageRef<'a, T, ConstUsize<1>, usize>)> {
+        iter::Columns::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_height.value(), 0..self.roi_width.value())
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRef<'a, T, W, H> {
+    /// Get a view of this image mirrored left and right.
+    pub fn flip_x(&self) -> ImageRefStrided<'a, T, W, H> {
+        ImageRefStrided::from(*self).flip_x()
+    }
+
+    /// Get a view of this image mirrored upside down.
+    pub fn flip_y(&self) -> ImageRefStrided<'a, T, W, H> {
+        ImageRefStrided::from(*self).flip_y()
+    }
+
+    /// Get a view of this image with x and y axes swapped.
+    pub fn transpose(&self) -> ImageRefStrided<'a, T, H, W> {
+        ImageRefStrided::from(*self).transpose()
+    }
+
+    /// Get a view of this image rotated by 90 degrees clockwise.
+    pub fn rotate90(&self) -> ImageRefStrided<'a, T, H, W> {
+        ImageRefStrided::from(*self).rotate90()
+    }
+
+    /// Get a view of this image rotated by 180 degrees.
+    pub fn rotate180(&self) -> ImageRefStrided<'a, T, W, H> {
+        ImageRefStrided::from(*self).rotate180()
+    }
+
+    /// Get a view of this image rotated by 270 degrees clockwise.
+    pub fn rotate270(&self) -> ImageRefStrided<'a, T, H, W> {
+        ImageRefStrided::from(*self).rotate270()
+    }
+}
+
 impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for ImageRef<'a, T, W, H> {
     type Width = W;
     type Height = H;
@@ -211,6 +313,27 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H>
     }
 }
 
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Borrow data mutably as an image whose rows start every stride elements.
+    /// If stride < width or data is too short to hold height rows, this function returns None.
+    pub fn from_slice(data: &'a mut [T], width: W, height: H, stride: usize) -> Option<Self> {
+        if required_len(width.value(), height.value(), stride).map_or(false, |len| len <= data.len()) {
+            Some(ImageRefMut::new(stride, data.as_mut_ptr(), 0, 0, width, height))
+        } else {
+            None
+        }
+    }
+
+    /// Borrow memory pointed by ptr mutably as an image whose rows start every stride elements.
+    /// # Safety
+    /// stride should be greater than or equal to width, and ptr should be valid for reads and writes of height rows for lifetime 'a
+    /// without being accessed through any other reference.
+    pub unsafe fn from_raw_parts(ptr: *mut T, width: W, height: H, stride: usize) -> Self {
+        debug_assert!(stride >= width.value(), "stride {} is less than width {} in ImageRefMut::from_raw_parts", stride, width);
+        ImageRefMut::new(stride, ptr, 0, 0, width, height)
+    }
+}
+
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRefMut<'a, T, W, H> {
     type Item = T;
 
@@ -262,17 +385,7 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> View for ImageRefMut<'a,
     }
 
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<T, RW, RH> {
-        let valid_x = x.clamp(0, self.roi_width.value() as isize) as usize;
-        let valid_y = y.clamp(0, self.roi_height.value() as isize) as usize;
-        let valid_width = (x + w.value() as isize).clamp(0, self.roi_width.value() as isize) as usize - valid_x;
-        let valid_height = (y + h.value() as isize).clamp(0, self.roi_height.value() as isize) as usize - valid_y;
-        ImageRefOverhang::new(
-            unsafe { self.view_unchecked(valid_x, valid_y, valid_width, valid_height) },
-            (-x).max(0) as usize,
-            (-y).max(0) as usize,
-            w,
-            h,
-        )
+        ImageRefOverhang::new(unsafe { self.view_unchecked(0, 0, self.roi_width.value(), self.roi_height.value()) }, x, y, w, h)
     }
 }
 
@@ -290,17 +403,7 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ViewMut for ImageRefMut<
     }
 
     fn view_overhang_mut<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&mut self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhangMut<T, RW, RH> {
-        let valid_x = x.clamp(0, self.roi_width.value() as isize) as usize;
-        let valid_y = y.clamp(0, self.roi_height.value() as isize) as usize;
-        let valid_width = (x + w.value() as isize).clamp(0, self.roi_width.value() as isize) as usize - valid_x;
-        let valid_height = (y + h.value() as isize).clamp(0, self.roi_height.value() as isize) as usize - valid_y;
-        ImageRefOverhangMut::new(
-            unsafe { self.view_unchecked_mut(valid_x, valid_y, valid_width, valid_height) },
-            (-x).max(0) as usize,
-            (-y).max(0) as usize,
-            w,
-            h,
-        )
+        ImageRefOverhangMut::new(unsafe { self.view_unchecked_mut(0, 0, self.roi_width.value(), self.roi_height.value()) }, x, y, w, h)
     }
 }
 
@@ -324,6 +427,20 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'
     }
 }
 
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate windows of size (kernel_width, kernel_height) centered on each pixel.
+    /// Pixels of a window outside of this image are not valid in the window.
+    pub fn pix_iter_window<KW: MayBeConst<usize>, KH: MayBeConst<usize>>(&self, kernel_width: KW, kernel_height: KH) -> PixIter<iter::IterWindow<'_, T, KW, KH>, W, H> {
+        let &ImageRefMut { roi_width, roi_height, .. } = self;
+        let image = ImageRef::new(self.base_width, self.ptr, self.roi_x, self.roi_y, roi_width.value(), roi_height.value());
+        PixIter::new(
+            iter::IterWindow::new(image, kernel_width, kernel_height, 0..roi_width.value() * roi_height.value()),
+            roi_width,
+            roi_height,
+        )
+    }
+}
+
 impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
     pub fn pix_iter_mut(&mut self) -> PixIter<iter::IterMut<'a, T>, W, H> {
         let &mut ImageRefMut {
@@ -344,6 +461,27 @@ impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'
     }
 }
 
+impl<'a, T: 'a + Send + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate disjoint mutable tiles of size (tile_width, tile_height) covering this image in parallel.
+    /// Tiles on the right and bottom edges may be smaller. The iterator has the size of the tile grid.
+    /// # Panics
+    /// Panics if tile_width or tile_height is 0.
+    pub fn par_tiles_mut(&mut self, tile_width: usize, tile_height: usize) -> PixIter<iter::TilesMut<'_, T>, usize, usize> {
+        self.reborrow().into_tiles_mut(tile_width, tile_height)
+    }
+
+    /// Consume this view into its mutable tiles, for owners of the data which lend the whole view.
+    pub(crate) fn into_tiles_mut(self, tile_width: usize, tile_height: usize) -> PixIter<iter::TilesMut<'a, T>, usize, usize> {
+        let (width, height) = (self.roi_width.value(), self.roi_height.value());
+        let image = ImageRefMut::new(self.base_width, self.ptr, self.roi_x, self.roi_y, width, height);
+        PixIter::new(
+            iter::TilesMut::new(image, tile_width, tile_height),
+            iter::TilesMut::<T>::count(width, tile_width),
+            iter::TilesMut::<T>::count(height, tile_height),
+        )
+    }
+}
+
 impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
     pub fn pix_iter_serialized(&self) -> SerializePixIter<iter::Iter<'a, T>, W, H> {
         let &ImageRefMut {
@@ -382,6 +520,106 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W
     }
 }
 
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate rows of this image as (y, row).
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = (usize, &'_ [T])> + DoubleEndedIterator {
+        iter::Rows::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_width.value(), 0..self.roi_height.value())
+    }
+
+    /// Iterate columns of this image as (x, column).
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = (usize, ImageRef<'_, T, ConstUsize<1>, usize>)> + DoubleEndedIterator {
+        iter::Columns::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_height.value(), 0..self.roi_width.value())
+    }
+}
+
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate rows of this image as (y, row) in parallel.
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = (usize, &'_ [T])> {
+        iter::Rows::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_width.value(), 0..self.roi_height.value())
+    }
+
+    /// Iterate columns of this image as (x, column) in parallel.
+    pub fn par_columns(&self) -> impl IndexedParallelIterator<Item = (usize, ImageRef<'_, T, ConstUsize<1>, usize>)> {
+        iter::Columns::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_height.value(), 0..self.roi_width.value())
+    }
+}
+
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate mutable rows of this image as (y, row).
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = (usize, &'_ mut [T])> + DoubleEndedIterator {
+        self.reborrow().into_rows_mut()
+    }
+
+    /// Iterate mutable columns of this image as (x, column).
+    pub fn columns_mut(&mut self) -> impl ExactSizeIterator<Item = (usize, ImageRefMut<'_, T, ConstUsize<1>, usize>)> + DoubleEndedIterator {
+        self.reborrow().into_columns_mut()
+    }
+
+    fn reborrow(&mut self) -> ImageRefMut<'_, T, W, H> {
+        ImageRefMut::new(self.base_width, self.ptr, self.roi_x, self.roi_y, self.roi_width, self.roi_height)
+    }
+
+    /// Consume this view into its mutable rows, for owners of the data which lend the whole view.
+    pub(crate) fn into_rows_mut(self) -> iter::RowsMut<'a, T> {
+        iter::RowsMut::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_width.value(), 0..self.roi_height.value())
+    }
+
+    /// Consume this view into its mutable columns, for owners of the data which lend the whole view.
+    pub(crate) fn into_columns_mut(self) -> iter::ColumnsMut<'a, T> {
+        iter::ColumnsMut::new(self.ptr, self.base_width, self.roi_x, self.roi_y, self.roi_height.value(), 0..self.roi_width.value())
+    }
+}
+
+impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate mutable rows of this image as (y, row) in parallel.
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, &'_ mut [T])> {
+        self.reborrow().into_rows_mut()
+    }
+}
+
+impl<'a, T: 'a + Send + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    /// Iterate mutable columns of this image as (x, column) in parallel.
+    pub fn par_columns_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, ImageRefMut<'_, T, ConstUsize<1>, usize>)> {
+        self.reborrow().into_columns_mut()
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefMut<'a, T, W, H> {
+    fn strided_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        ImageRefStridedMut::from(ImageRefMut::new(self.base_width, self.ptr, self.roi_x, self.roi_y, self.roi_width, self.roi_height))
+    }
+
+    /// Get a mutable view of this image mirrored left and right.
+    pub fn flip_x_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        self.strided_mut().flip_x()
+    }
+
+    /// Get a mutable view of this image mirrored upside down.
+    pub fn flip_y_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        self.strided_mut().flip_y()
+    }
+
+    /// Get a mutable view of this image with x and y axes swapped.
+    pub fn transpose_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        self.strided_mut().transpose()
+    }
+
+    /// Get a mutable view of this image rotated by 90 degrees clockwise.
+    pub fn rotate90_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        self.strided_mut().rotate90()
+    }
+
+    /// Get a mutable view of this image rotated by 180 degrees.
+    pub fn rotate180_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        self.strided_mut().rotate180()
+    }
+
+    /// Get a mutable view of this image rotated by 270 degrees clockwise.
+    pub fn rotate270_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        self.strided_mut().rotate270()
+    }
+}
+
 impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for ImageRefMut<'a, T, W, H> {
     type Width = W;
     type Height = H;
@@ -433,6 +671,9 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelI
 }
 
 pub struct ImageRefOverhang<'a, T, W: MayBeConst<usize> = usize, H: MayBeConst<usize> = usize> {
+    source: ImageRef<'a, T, usize, usize>,
+    source_x: isize,
+    source_y: isize,
     valid_ref: ImageRef<'a, T, usize, usize>,
     valid_offset_x: usize,
     valid_offset_y: usize,
@@ -445,15 +686,37 @@ unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Send for Im
 unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Sync for ImageRefOverhang<'a, T, W, H> {}
 
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhang<'a, T, W, H> {
-    pub(crate) fn new(valid_ref: ImageRef<'a, T, usize, usize>, valid_offset_x: usize, valid_offset_y: usize, width: W, height: H) -> Self {
+    pub(crate) fn new(source: ImageRef<'a, T, usize, usize>, source_x: isize, source_y: isize, width: W, height: H) -> Self {
+        let valid_x = source_x.clamp(0, source.roi_width as isize) as usize;
+        let valid_y = source_y.clamp(0, source.roi_height as isize) as usize;
+        let valid_width = (source_x + width.value() as isize).clamp(0, source.roi_width as isize) as usize - valid_x;
+        let valid_height = (source_y + height.value() as isize).clamp(0, source.roi_height as isize) as usize - valid_y;
         Self {
-            valid_ref,
-            valid_offset_x,
-            valid_offset_y,
+            source,
+            source_x,
+            source_y,
+            valid_ref: ImageRef::new(source.base_width, source.ptr, source.roi_x + valid_x, source.roi_y + valid_y, valid_width, valid_height),
+            valid_offset_x: (-source_x).max(0) as usize,
+            valid_offset_y: (-source_y).max(0) as usize,
             width,
             height,
         }
     }
+
+    /// Get value reference of pixel (x, y).
+    /// If pixel (x, y) is outside of the image this view was taken from, it is resolved by `border`.
+    /// # Panics
+    /// Panics if that image is empty and `border` is not Border::Constant.
+    pub fn get_border<'b>(&'b self, x: usize, y: usize, border: &'b Border<T>) -> &'b T {
+        resolve_border(&self.source, self.source_x + x as isize, self.source_y + y as isize, border)
+    }
+}
+
+fn resolve_border<'b, T>(source: &ImageRef<'b, T, usize, usize>, x: isize, y: isize, border: &'b Border<T>) -> &'b T {
+    match (border.map(x, source.roi_width), border.map(y, source.roi_height)) {
+        (Some(x), Some(y)) => unsafe { &*source.ptr.add((source.roi_y + y) * source.base_width + source.roi_x + x) },
+        _ => border.constant().expect("an empty image cannot be extended by this border"),
+    }
 }
 
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRefOverhang<'a, T, W, H> {
@@ -501,7 +764,13 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> View for ImageRefOverhan
     }
 
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<T, RW, RH> {
-        self.valid_ref.view_overhang(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h)
+        let view = self.valid_ref.view_overhang(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h);
+        ImageRefOverhang {
+            source: self.source,
+            source_x: self.source_x + x,
+            source_y: self.source_y + y,
+            ..view
+        }
     }
 }
 
@@ -522,6 +791,7 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverh
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -539,6 +809,15 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverh
             height,
         )
     }
+
+    /// Iterate pixels of this view, resolving pixels outside of the source image by `border`.
+    pub fn pix_iter_border<'b>(&self, border: &'b Border<T>) -> PixIter<iter::IterBorder<'b, T>, W, H>
+    where
+        'a: 'b,
+    {
+        let iter = self.pix_iter().into_inner();
+        PixIter::new(iter::IterBorder::new(iter, self.source, self.source_x, self.source_y, border), self.width, self.height)
+    }
 }
 
 impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhang<'a, T, W, H> {
@@ -558,6 +837,7 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhang<'a,
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -575,6 +855,15 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhang<'a,
             height,
         )
     }
+
+    /// Iterate pixels of this view serially, resolving pixels outside of the source image by `border`.
+    pub fn pix_iter_serialized_border<'b>(&self, border: &'b Border<T>) -> SerializePixIter<iter::IterBorder<'b, T>, W, H>
+    where
+        'a: 'b,
+    {
+        let iter = self.pix_iter_serialized().into_inner();
+        SerializePixIter::new(iter::IterBorder::new(iter, self.source, self.source_x, self.source_y, border), self.width, self.height)
+    }
 }
 
 impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for ImageRefOverhang<'a, T, W, H> {
@@ -599,6 +888,7 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIter
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -640,6 +930,7 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelI
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -660,6 +951,9 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelI
 }
 
 pub struct ImageRefOverhangMut<'a, T, W: MayBeConst<usize> = usize, H: MayBeConst<usize> = usize> {
+    source: ImageRef<'a, T, usize, usize>,
+    source_x: isize,
+    source_y: isize,
     valid_ref: ImageRefMut<'a, T, usize, usize>,
     valid_offset_x: usize,
     valid_offset_y: usize,
@@ -672,15 +966,30 @@ unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Send for Im
 unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Sync for ImageRefOverhangMut<'a, T, W, H> {}
 
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhangMut<'a, T, W, H> {
-    pub(crate) fn new(valid_ref: ImageRefMut<'a, T, usize, usize>, valid_offset_x: usize, valid_offset_y: usize, width: W, height: H) -> Self {
+    pub(crate) fn new(source: ImageRefMut<'a, T, usize, usize>, source_x: isize, source_y: isize, width: W, height: H) -> Self {
+        let valid_x = source_x.clamp(0, source.roi_width as isize) as usize;
+        let valid_y = source_y.clamp(0, source.roi_height as isize) as usize;
+        let valid_width = (source_x + width.value() as isize).clamp(0, source.roi_width as isize) as usize - valid_x;
+        let valid_height = (source_y + height.value() as isize).clamp(0, source.roi_height as isize) as usize - valid_y;
         Self {
-            valid_ref,
-            valid_offset_x,
-            valid_offset_y,
+            source: ImageRef::new(source.base_width, source.ptr, source.roi_x, source.roi_y, source.roi_width, source.roi_height),
+            source_x,
+            source_y,
+            valid_ref: ImageRefMut::new(source.base_width, source.ptr, source.roi_x + valid_x, source.roi_y + valid_y, valid_width, valid_height),
+            valid_offset_x: (-source_x).max(0) as usize,
+            valid_offset_y: (-source_y).max(0) as usize,
             width,
             height,
         }
     }
+
+    /// Get value reference of pixel (x, y).
+    /// If pixel (x, y) is outside of the image this view was taken from, it is resolved by `border`.
+    /// # Panics
+    /// Panics if that image is empty and `border` is not Border::Constant.
+    pub fn get_border<'b>(&'b self, x: usize, y: usize, border: &'b Border<T>) -> &'b T {
+        resolve_border(&self.source, self.source_x + x as isize, self.source_y + y as isize, border)
+    }
 }
 
 impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRefOverhangMut<'a, T, W, H> {
@@ -735,7 +1044,13 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> View for ImageRefOverhan
     }
 
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<T, RW, RH> {
-        self.valid_ref.view_overhang(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h)
+        let view = self.valid_ref.view_overhang(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h);
+        ImageRefOverhang {
+            source: self.source,
+            source_x: self.source_x + x,
+            source_y: self.source_y + y,
+            ..view
+        }
     }
 }
 
@@ -753,7 +1068,13 @@ impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ViewMut for ImageRefOver
     }
 
     fn view_overhang_mut<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&mut self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhangMut<T, RW, RH> {
-        self.valid_ref.view_overhang_mut(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h)
+        let view = self.valid_ref.view_overhang_mut(x - self.valid_offset_x as isize, y - self.valid_offset_y as isize, w, h);
+        ImageRefOverhangMut {
+            source: self.source,
+            source_x: self.source_x + x,
+            source_y: self.source_y + y,
+            ..view
+        }
     }
 }
 
@@ -774,6 +1095,7 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverh
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -791,6 +1113,12 @@ impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverh
             height,
         )
     }
+
+    /// Iterate pixels of this view, resolving pixels outside of the source image by `border`.
+    pub fn pix_iter_border<'b>(&'b self, border: &'b Border<T>) -> PixIter<iter::IterBorder<'b, T>, W, H> {
+        let iter = self.pix_iter().into_inner();
+        PixIter::new(iter::IterBorder::new(iter, self.source, self.source_x, self.source_y, border), self.width, self.height)
+    }
 }
 
 impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhangMut<'a, T, W, H> {
@@ -810,6 +1138,7 @@ impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverh
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -846,6 +1175,7 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhangMut<
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -864,6 +1194,12 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhangMut<
         )
     }
 
+    /// Iterate pixels of this view serially, resolving pixels outside of the source image by `border`.
+    pub fn pix_iter_serialized_border<'b>(&'b self, border: &'b Border<T>) -> SerializePixIter<iter::IterBorder<'b, T>, W, H> {
+        let iter = self.pix_iter_serialized().into_inner();
+        SerializePixIter::new(iter::IterBorder::new(iter, self.source, self.source_x, self.source_y, border), self.width, self.height)
+    }
+
     pub fn pix_iter_serialized_mut(&mut self) -> SerializePixIter<iter::IterOverhang<iter::IterMut<'a, T>>, W, H> {
         let &mut ImageRefOverhangMut {
             valid_ref:
@@ -880,6 +1216,7 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefOverhangMut<
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -921,6 +1258,7 @@ impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIter
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -962,6 +1300,7 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelI
             valid_offset_y,
             width,
             height,
+            ..
         } = self;
         let offset = roi_y * roi_width;
 
@@ -981,8 +1320,331 @@ impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelI
     }
 }
 
+/// An area reference of image whose pixels are located with signed strides, like flipped, transposed or rotated images.
+pub struct ImageRefStrided<'a, T, W: MayBeConst<usize> = usize, H: MayBeConst<usize> = usize> {
+    ptr: *const T,
+    step_x: isize,
+    step_y: isize,
+    width: W,
+    height: H,
+    lifetime: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Send for ImageRefStrided<'a, T, W, H> {}
+
+unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Sync for ImageRefStrided<'a, T, W, H> {}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStrided<'a, T, W, H> {
+    pub(crate) fn new(ptr: *const T, step_x: isize, step_y: isize, width: W, height: H) -> Self {
+        Self {
+            ptr,
+            step_x,
+            step_y,
+            width,
+            height,
+            lifetime: Default::default(),
+        }
+    }
+
+    pub(crate) fn subsample(self, step_x: usize, step_y: usize) -> ImageRefStrided<'a, T> {
+        let width = self.width.value() / step_x + (self.width.value() % step_x != 0) as usize;
+        let height = self.height.value() / step_y + (self.height.value() % step_y != 0) as usize;
+        ImageRefStrided::new(self.ptr, self.step_x * step_x as isize, self.step_y * step_y as isize, width, height)
+    }
+
+    /// Mirror this image left and right.
+    pub fn flip_x(self) -> ImageRefStrided<'a, T, W, H> {
+        let ptr = self.ptr.wrapping_offset((self.width.value() as isize - 1) * self.step_x);
+        ImageRefStrided::new(ptr, -self.step_x, self.step_y, self.width, self.height)
+    }
+
+    /// Mirror this image upside down.
+    pub fn flip_y(self) -> ImageRefStrided<'a, T, W, H> {
+        let ptr = self.ptr.wrapping_offset((self.height.value() as isize - 1) * self.step_y);
+        ImageRefStrided::new(ptr, self.step_x, -self.step_y, self.width, self.height)
+    }
+
+    /// Swap x and y axes of this image.
+    pub fn transpose(self) -> ImageRefStrided<'a, T, H, W> {
+        ImageRefStrided::new(self.ptr, self.step_y, self.step_x, self.height, self.width)
+    }
+
+    /// Rotate this image by 90 degrees clockwise.
+    pub fn rotate90(self) -> ImageRefStrided<'a, T, H, W> {
+        self.flip_y().transpose()
+    }
+
+    /// Rotate this image by 180 degrees.
+    pub fn rotate180(self) -> ImageRefStrided<'a, T, W, H> {
+        self.flip_x().flip_y()
+    }
+
+    /// Rotate this image by 270 degrees clockwise.
+    pub fn rotate270(self) -> ImageRefStrided<'a, T, H, W> {
+        self.flip_x().transpose()
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRefStrided<'a, T, W, H> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.width.value()
+    }
+
+    fn height(&self) -> usize {
+        self.height.value()
+    }
+
+    fn valid_rect(&self) -> Rectangle {
+        Rectangle {
+            x: 0,
+            y: 0,
+            w: self.width.value(),
+            h: self.height.value(),
+        }
+    }
+
+    unsafe fn get_unchecked(&self, x: usize, y: usize) -> &Self::Item {
+        debug_assert!(self.is_valid(x, y), "Locate ({}, {}) is not valid in ImageRefStrided::get_unchecked", x, y);
+        &*self.ptr.offset(x as isize * self.step_x + y as isize * self.step_y)
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> Clone for ImageRefStrided<'a, T, W, H> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> Copy for ImageRefStrided<'a, T, W, H> {}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> From<ImageRef<'a, T, W, H>> for ImageRefStrided<'a, T, W, H> {
+    fn from(image: ImageRef<'a, T, W, H>) -> Self {
+        let ptr = image.ptr.wrapping_add(image.roi_y * image.base_width + image.roi_x);
+        ImageRefStrided::new(ptr, 1, image.base_width as isize, image.roi_width, image.roi_height)
+    }
+}
+
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStrided<'a, T, W, H> {
+    pub fn pix_iter(&self) -> PixIter<iter::IterStrided<'a, T>, W, H> {
+        (*self).into_pix_iter()
+    }
+}
+
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStrided<'a, T, W, H> {
+    pub fn pix_iter_serialized(&self) -> SerializePixIter<iter::IterStrided<'a, T>, W, H> {
+        (*self).into_pix_iter_serialized()
+    }
+}
+
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for ImageRefStrided<'a, T, W, H> {
+    type Width = W;
+    type Height = H;
+    type Item = &'a T;
+    type Iter = iter::IterStrided<'a, T>;
+
+    fn into_pix_iter(self) -> PixIter<iter::IterStrided<'a, T>, W, H> {
+        let ImageRefStrided {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        PixIter::new(iter::IterStrided::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelIterator for ImageRefStrided<'a, T, W, H> {
+    type Width = W;
+    type Height = H;
+    type Item = &'a T;
+    type Iter = iter::IterStrided<'a, T>;
+
+    fn into_pix_iter_serialized(self) -> SerializePixIter<iter::IterStrided<'a, T>, W, H> {
+        let ImageRefStrided {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        SerializePixIter::new(iter::IterStrided::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+/// An area reference of image whose pixels are located with signed strides, like flipped, transposed or rotated images.
+pub struct ImageRefStridedMut<'a, T, W: MayBeConst<usize> = usize, H: MayBeConst<usize> = usize> {
+    ptr: *mut T,
+    step_x: isize,
+    step_y: isize,
+    width: W,
+    height: H,
+    lifetime: PhantomData<&'a mut ()>,
+}
+
+unsafe impl<'a, T: Send, W: MayBeConst<usize>, H: MayBeConst<usize>> Send for ImageRefStridedMut<'a, T, W, H> {}
+
+unsafe impl<'a, T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> Sync for ImageRefStridedMut<'a, T, W, H> {}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStridedMut<'a, T, W, H> {
+    pub(crate) fn new(ptr: *mut T, step_x: isize, step_y: isize, width: W, height: H) -> Self {
+        Self {
+            ptr,
+            step_x,
+            step_y,
+            width,
+            height,
+            lifetime: Default::default(),
+        }
+    }
+
+    pub(crate) fn subsample(self, step_x: usize, step_y: usize) -> ImageRefStridedMut<'a, T> {
+        let width = self.width.value() / step_x + (self.width.value() % step_x != 0) as usize;
+        let height = self.height.value() / step_y + (self.height.value() % step_y != 0) as usize;
+        ImageRefStridedMut::new(self.ptr, self.step_x * step_x as isize, self.step_y * step_y as isize, width, height)
+    }
+
+    /// Mirror this image left and right.
+    pub fn flip_x(self) -> ImageRefStridedMut<'a, T, W, H> {
+        let ptr = self.ptr.wrapping_offset((self.width.value() as isize - 1) * self.step_x);
+        ImageRefStridedMut::new(ptr, -self.step_x, self.step_y, self.width, self.height)
+    }
+
+    /// Mirror this image upside down.
+    pub fn flip_y(self) -> ImageRefStridedMut<'a, T, W, H> {
+        let ptr = self.ptr.wrapping_offset((self.height.value() as isize - 1) * self.step_y);
+        ImageRefStridedMut::new(ptr, self.step_x, -self.step_y, self.width, self.height)
+    }
+
+    /// Swap x and y axes of this image.
+    pub fn transpose(self) -> ImageRefStridedMut<'a, T, H, W> {
+        ImageRefStridedMut::new(self.ptr, self.step_y, self.step_x, self.height, self.width)
+    }
+
+    /// Rotate this image by 90 degrees clockwise.
+    pub fn rotate90(self) -> ImageRefStridedMut<'a, T, H, W> {
+        self.flip_y().transpose()
+    }
+
+    /// Rotate this image by 180 degrees.
+    pub fn rotate180(self) -> ImageRefStridedMut<'a, T, W, H> {
+        self.flip_x().flip_y()
+    }
+
+    /// Rotate this image by 270 degrees clockwise.
+    pub fn rotate270(self) -> ImageRefStridedMut<'a, T, H, W> {
+        self.flip_x().transpose()
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for ImageRefStridedMut<'a, T, W, H> {
+    type Item = T;
+
+    fn width(&self) -> usize {
+        self.width.value()
+    }
+
+    fn height(&self) -> usize {
+        self.height.value()
+    }
+
+    fn valid_rect(&self) -> Rectangle {
+        Rectangle {
+            x: 0,
+            y: 0,
+            w: self.width.value(),
+            h: self.height.value(),
+        }
+    }
+
+    unsafe fn get_unchecked(&self, x: usize, y: usize) -> &Self::Item {
+        debug_assert!(self.is_valid(x, y), "Locate ({}, {}) is not valid in ImageRefStridedMut::get_unchecked", x, y);
+        &*self.ptr.offset(x as isize * self.step_x + y as isize * self.step_y)
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> WritePixel for ImageRefStridedMut<'a, T, W, H> {
+    unsafe fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut Self::Item {
+        debug_assert!(self.is_valid(x, y), "Locate ({}, {}) is not valid in ImageRefStridedMut::get_unchecked_mut", x, y);
+        &mut *self.ptr.offset(x as isize * self.step_x + y as isize * self.step_y)
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> From<ImageRefMut<'a, T, W, H>> for ImageRefStridedMut<'a, T, W, H> {
+    fn from(image: ImageRefMut<'a, T, W, H>) -> Self {
+        let ptr = image.ptr.wrapping_add(image.roi_y * image.base_width + image.roi_x);
+        ImageRefStridedMut::new(ptr, 1, image.base_width as isize, image.roi_width, image.roi_height)
+    }
+}
+
+impl<'a, T, W: MayBeConst<usize>, H: MayBeConst<usize>> From<ImageRefStridedMut<'a, T, W, H>> for ImageRefStrided<'a, T, W, H> {
+    fn from(image: ImageRefStridedMut<'a, T, W, H>) -> Self {
+        ImageRefStrided::new(image.ptr, image.step_x, image.step_y, image.width, image.height)
+    }
+}
+
+impl<'a, T: 'a + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStridedMut<'a, T, W, H> {
+    pub fn pix_iter(&self) -> PixIter<iter::IterStrided<'_, T>, W, H> {
+        let &ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        PixIter::new(iter::IterStrided::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStridedMut<'a, T, W, H> {
+    pub fn pix_iter_mut(&mut self) -> PixIter<iter::IterStridedMut<'_, T>, W, H> {
+        let &mut ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        PixIter::new(iter::IterStridedMut::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> ImageRefStridedMut<'a, T, W, H> {
+    pub fn pix_iter_serialized(&self) -> SerializePixIter<iter::IterStrided<'_, T>, W, H> {
+        let &ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        SerializePixIter::new(iter::IterStrided::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+
+    pub fn pix_iter_serialized_mut(&mut self) -> SerializePixIter<iter::IterStridedMut<'_, T>, W, H> {
+        let &mut ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        SerializePixIter::new(iter::IterStridedMut::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+impl<'a, T: 'a + Send, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for ImageRefStridedMut<'a, T, W, H> {
+    type Width = W;
+    type Height = H;
+    type Item = &'a mut T;
+    type Iter = iter::IterStridedMut<'a, T>;
+
+    fn into_pix_iter(self) -> PixIter<iter::IterStridedMut<'a, T>, W, H> {
+        let ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        PixIter::new(iter::IterStridedMut::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
+impl<'a, T: 'a, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelIterator for ImageRefStridedMut<'a, T, W, H> {
+    type Width = W;
+    type Height = H;
+    type Item = &'a mut T;
+    type Iter = iter::IterStridedMut<'a, T>;
+
+    fn into_pix_iter_serialized(self) -> SerializePixIter<iter::IterStridedMut<'a, T>, W, H> {
+        let ImageRefStridedMut {
+            ptr, step_x, step_y, width, height, ..
+        } = self;
+        SerializePixIter::new(iter::IterStridedMut::new(ptr, step_x, step_y, width.value(), 0..width.value() * height.value()), width, height)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use rayon::prelude::ParallelIterator;
+
+    use crate::border::Border;
+    use crate::image_ref::{ImageRef, ImageRefMut, ImageRefStrided};
     use crate::physical_image::PhysicalImage;
     use crate::{IntoPixelIterator, IntoSerializedPixelIterator, ReadPixel, View, ViewMut, WritePixel};
 
@@ -1214,6 +1876,246 @@ mod tests {
         assert!(image_ref.view_mut(0, 0, 10, 10).is_some());
     }
 
+    #[test]
+    fn overhang_border() {
+        const WIDTH: usize = 5;
+        const HEIGHT: usize = 4;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = WIDTH * y + x;
+            }
+        }
+        let borders = [Border::Constant(100), Border::Clamp, Border::Reflect, Border::Reflect101, Border::Wrap];
+        for border in borders.iter() {
+            for &(x, y) in [(-3, -2), (2, 1), (-1, 3)].iter() {
+                let image_ref = image.view_overhang(x, y, 11, 8);
+                let mut expect = Vec::with_capacity(11 * 8);
+                for dy in 0..8 {
+                    for dx in 0..11 {
+                        let value = match (border.map(x + dx, WIDTH), border.map(y + dy, HEIGHT)) {
+                            (Some(x), Some(y)) => y * WIDTH + x,
+                            _ => 100,
+                        };
+                        assert_eq!(image_ref.get_border(dx as usize, dy as usize, border), &value);
+                        expect.push(value);
+                    }
+                }
+                assert_eq!(image_ref.pix_iter_border(border).collect_image().data, expect.iter().collect::<Vec<_>>());
+                assert_eq!(image_ref.pix_iter_serialized_border(border).collect_image().data, expect.iter().collect::<Vec<_>>());
+                assert_eq!(
+                    image_ref.pix_iter_serialized_border(border).into_inner().rev().collect::<Vec<_>>(),
+                    expect.iter().rev().collect::<Vec<_>>()
+                );
+                let nested_expect = (2..7).flat_map(|dy| expect[dy * 11 + 1..dy * 11 + 10].iter()).collect::<Vec<_>>();
+                let nested = image_ref.view_overhang(1, 2, 9, 5);
+                assert_eq!(nested.get_border(0, 0, border), nested_expect[0]);
+                assert_eq!(nested.pix_iter_border(border).collect_image().data, nested_expect);
+
+                let mut image_mut = image.view_overhang_mut(x, y, 11, 8);
+                assert_eq!(image_mut.get_border(10, 7, border), &expect[11 * 8 - 1]);
+                assert_eq!(image_mut.pix_iter_border(border).collect_image().data, expect.iter().collect::<Vec<_>>());
+                let nested = image_mut.view_overhang_mut(1, 2, 9, 5);
+                assert_eq!(Iterator::collect::<Vec<_>>(nested.pix_iter_serialized_border(border).into_inner()), nested_expect);
+            }
+        }
+        let image_ref = image.view(1, 1, 3, 2).unwrap();
+        let image_ref = image_ref.view_overhang(-1, -1, 5, 4);
+        assert_eq!(image_ref.get_border(0, 0, &Border::Clamp), &(WIDTH + 1));
+        assert_eq!(image_ref.get_border(4, 3, &Border::Wrap), &(WIDTH + 1));
+        assert_eq!(image_ref.get_border(4, 3, &Border::Constant(100)), &100);
+    }
+
+    #[test]
+    fn split() {
+        const WIDTH: usize = 20;
+        const HEIGHT: usize = 10;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = WIDTH * y + x;
+            }
+        }
+        assert!(image.split_at_x(WIDTH + 1).is_none());
+        assert!(image.split_at_y(HEIGHT + 1).is_none());
+        let (mut left, mut right) = image.split_at_x(8).unwrap();
+        assert_eq!((left.width(), left.height()), (8, HEIGHT));
+        assert_eq!((right.width(), right.height()), (WIDTH - 8, HEIGHT));
+        let (mut top, mut bottom) = right.split_at_y(3).unwrap();
+        assert_eq!((top.width(), top.height()), (WIDTH - 8, 3));
+        assert_eq!((bottom.width(), bottom.height()), (WIDTH - 8, HEIGHT - 3));
+        assert_eq!(top.get(0, 0), Some(&8));
+        assert_eq!(bottom.get(1, 1), Some(&(WIDTH * 4 + 9)));
+        *left.get_mut(7, 9).unwrap() = 0;
+        *top.get_mut(11, 2).unwrap() = 0;
+        *bottom.get_mut(0, 0).unwrap() = 0;
+        let (empty, all) = left.split_at_x(0).unwrap();
+        assert_eq!((empty.width(), all.width()), (0, 8));
+        assert_eq!(image.get(7, 9), Some(&0));
+        assert_eq!(image.get(WIDTH - 1, 2), Some(&0));
+        assert_eq!(image.get(8, 3), Some(&0));
+
+        let mut overhang = image.view_overhang_mut(0, 0, 5, 5);
+        assert!(overhang.split_at_x(2).is_some());
+        let mut overhang = image.view_overhang_mut(-1, 0, 5, 5);
+        assert!(overhang.split_at_x(2).is_none());
+        assert!(overhang.split_at_y(2).is_none());
+    }
+
+    #[test]
+    fn transform() {
+        const WIDTH: usize = 7;
+        const HEIGHT: usize = 5;
+        let image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| (x, y));
+        let check = |view: &ImageRefStrided<(usize, usize)>, width: usize, height: usize, f: &dyn Fn(usize, usize) -> (usize, usize)| {
+            assert_eq!((view.width(), view.height()), (width, height));
+            let expect = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect::<Vec<_>>();
+            for y in 0..height {
+                for x in 0..width {
+                    assert_eq!(view.get(x, y), Some(&f(x, y)));
+                }
+            }
+            assert_eq!(view.get(width, 0), None);
+            assert_eq!(view.pix_iter().collect_image().data.into_iter().copied().collect::<Vec<_>>(), expect);
+            assert_eq!(view.pix_iter_serialized().into_inner().rev().copied().collect::<Vec<_>>(), expect.into_iter().rev().collect::<Vec<_>>());
+        };
+        check(&image.flip_x(), WIDTH, HEIGHT, &|x, y| (WIDTH - 1 - x, y));
+        check(&image.flip_y(), WIDTH, HEIGHT, &|x, y| (x, HEIGHT - 1 - y));
+        check(&image.transpose(), HEIGHT, WIDTH, &|x, y| (y, x));
+        check(&image.rotate90(), HEIGHT, WIDTH, &|x, y| (y, HEIGHT - 1 - x));
+        check(&image.rotate180(), WIDTH, HEIGHT, &|x, y| (WIDTH - 1 - x, HEIGHT - 1 - y));
+        check(&image.rotate270(), HEIGHT, WIDTH, &|x, y| (WIDTH - 1 - y, x));
+        check(&image.rotate90().rotate90().rotate90().rotate90(), WIDTH, HEIGHT, &|x, y| (x, y));
+        check(&image.view(2, 1, 4, 3).unwrap().rotate90(), 3, 4, &|x, y| (y + 2, 3 - x));
+        check(&image.view(2, 1, 4, 3).unwrap().transpose().flip_x(), 3, 4, &|x, y| (y + 2, 3 - x));
+
+        let mut image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| y * WIDTH + x);
+        let expect = PhysicalImage::from_fn(HEIGHT, WIDTH, |x, y| (HEIGHT - 1 - x) * WIDTH + y);
+        assert_eq!(image.rotate90().pix_iter().map(|v| *v).collect_image().data, expect.data);
+        ParallelIterator::for_each(image.rotate90_mut().pix_iter_mut().into_inner(), |v| *v += 100);
+        *image.flip_y_mut().get_mut(1, 0).unwrap() = 0;
+        assert_eq!(image.get(1, HEIGHT - 1), Some(&0));
+        let mut view = image.view_mut(1, 1, 3, 3).unwrap();
+        for (i, v) in view.transpose_mut().pix_iter_serialized_mut().into_inner().enumerate() {
+            *v = i;
+        }
+        assert_eq!(image.get(2, 1), Some(&3));
+        assert_eq!(image.get(1, 2), Some(&1));
+        assert_eq!(image.get(0, 0), Some(&100));
+
+        let empty = PhysicalImage::<u8>::new(0, 3);
+        assert_eq!(empty.rotate90().pix_iter().collect_image().data, Vec::<&u8>::new());
+        assert_eq!(empty.flip_x().pix_iter_serialized().into_inner().len(), 0);
+    }
+
+    #[test]
+    fn strided() {
+        const WIDTH: usize = 11;
+        const HEIGHT: usize = 8;
+        let mut image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| y * WIDTH + x);
+        let view = image.view_strided(0, 0, WIDTH, HEIGHT, 2, 2).unwrap();
+        assert_eq!((view.width(), view.height()), (6, 4));
+        for y in 0..4 {
+            for x in 0..6 {
+                assert_eq!(view.get(x, y), Some(&(y * 2 * WIDTH + x * 2)));
+            }
+        }
+        let expect = (0..4).flat_map(|y| (0..6).map(move |x| y * 2 * WIDTH + x * 2)).collect::<Vec<_>>();
+        assert_eq!(view.pix_iter().map(|v| *v).collect_image().data, expect);
+        assert_eq!(Iterator::copied(view.pix_iter_serialized().into_inner()).collect::<Vec<_>>(), expect);
+
+        let view = image.view(2, 1, 6, 7).unwrap();
+        let field = view.view_strided(1, 1, 5, 6, 3, 2).unwrap();
+        assert_eq!((field.width(), field.height()), (2, 3));
+        assert_eq!(
+            Iterator::copied(field.pix_iter_serialized().into_inner()).collect::<Vec<_>>(),
+            vec![2 * WIDTH + 3, 2 * WIDTH + 6, 4 * WIDTH + 3, 4 * WIDTH + 6, 6 * WIDTH + 3, 6 * WIDTH + 6]
+        );
+        assert_eq!(field.flip_y().get(0, 0), Some(&(6 * WIDTH + 3)));
+
+        assert!(image.view_strided(0, 0, WIDTH + 1, HEIGHT, 2, 2).is_none());
+        assert!(image.view_strided(0, 0, WIDTH, HEIGHT, 0, 2).is_none());
+        assert_eq!(image.view_strided(3, 3, 0, 0, 2, 2).unwrap().pix_iter_serialized().into_inner().len(), 0);
+
+        let mut odd = image.view_strided_mut(0, 1, WIDTH, HEIGHT - 1, 1, 2).unwrap();
+        ParallelIterator::for_each(odd.pix_iter_mut().into_inner(), |v| *v = 0);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(image.get(x, y), Some(&if y % 2 == 1 { 0 } else { y * WIDTH + x }));
+            }
+        }
+    }
+
+    #[test]
+    fn from_slice() {
+        let mut data = (0..24).collect::<Vec<usize>>();
+        let image = ImageRef::from_slice(&data, 3, 4, 7).unwrap();
+        assert_eq!((image.width(), image.height()), (3, 4));
+        assert_eq!(image.get(2, 3), Some(&23));
+        assert_eq!(image.get(3, 0), None);
+        assert_eq!(
+            Iterator::copied(image.pix_iter_serialized().into_inner()).collect::<Vec<_>>(),
+            vec![0, 1, 2, 7, 8, 9, 14, 15, 16, 21, 22, 23]
+        );
+        assert!(ImageRef::from_slice(&data, 3, 4, 2).is_none());
+        assert!(ImageRef::from_slice(&data[..23], 3, 4, 7).is_none());
+        assert!(ImageRef::from_slice(&data, 3, 4, usize::MAX).is_none());
+        assert!(ImageRef::from_slice(&data, 30, 0, 30).is_some());
+        let image = unsafe { ImageRef::from_raw_parts(data.as_ptr().add(1), 2, 2, 5) };
+        assert_eq!(image.view(1, 1, 1, 1).unwrap().get(0, 0), Some(&7));
+
+        let mut image = ImageRefMut::from_slice(&mut data, 2, 3, 10).unwrap();
+        ParallelIterator::for_each(image.pix_iter_mut().into_inner(), |v| *v = 100);
+        assert_eq!(&data[..12], &[100, 100, 2, 3, 4, 5, 6, 7, 8, 9, 100, 100]);
+        assert_eq!(&data[20..], &[100, 100, 22, 23]);
+        assert!(ImageRefMut::from_slice(&mut data, 2, 3, 12).is_none());
+        let mut image = unsafe { ImageRefMut::from_raw_parts(data.as_mut_ptr().add(2), 1, 2, 3) };
+        *image.get_mut(0, 1).unwrap() = 0;
+        assert_eq!(data[5], 0);
+    }
+
+    #[test]
+    fn rows_columns() {
+        const WIDTH: usize = 20;
+        const HEIGHT: usize = 15;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = WIDTH * y + x;
+            }
+        }
+        let image_ref = image.view(3, 4, 10, 5).unwrap();
+        let rows = image_ref.rows().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 5);
+        for (y, row) in rows {
+            assert_eq!(row, (0..10).map(|x| WIDTH * (y + 4) + x + 3).collect::<Vec<_>>().as_slice());
+        }
+        assert_eq!(image_ref.par_rows().collect::<Vec<_>>(), image_ref.rows().collect::<Vec<_>>());
+        let columns = image_ref.columns().collect::<Vec<_>>();
+        assert_eq!(columns.len(), 10);
+        for (x, column) in columns {
+            assert_eq!(column.height(), 5);
+            for y in 0..5 {
+                assert_eq!(column.get(0, y), Some(&(WIDTH * (y + 4) + x + 3)));
+            }
+            assert_eq!(column.get(1, 0), None);
+        }
+        assert_eq!(image_ref.par_columns().map(|(x, _)| x).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+
+        let mut image_ref = image.view_mut(3, 4, 10, 5).unwrap();
+        image_ref.par_rows_mut().for_each(|(_, row)| row.iter_mut().for_each(|value| *value = 0));
+        for (x, mut column) in image_ref.columns_mut() {
+            *column.get_mut(0, 4).unwrap() = x + 1;
+        }
+        assert_eq!(image_ref.rows().map(|(_, row)| row.iter().sum::<usize>()).collect::<Vec<_>>(), vec![0, 0, 0, 0, 55]);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let inside = (3..13).contains(&x) && (4..9).contains(&y);
+                assert_eq!(image.get(x, y) == Some(&(WIDTH * y + x)), !inside);
+            }
+        }
+    }
+
     #[test]
     fn iterator() {
         const WIDTH: usize = 50;
@@ -1,46 +1,1144 @@
 use std::marker::PhantomData;
 use std::ops::Range;
 
+use partial_const::{ConstUsize, MayBeConst};
 use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
 use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
 
+use crate::border::Border;
+use crate::image_ref::{resolve_border, ImageRef, ImageRefMut, ImageRefOverhang};
+
 pub struct Iter<'a, T> {
     ptr: *const T,
     base_width: usize,
-    offset_x: usize,
-    width: usize,
+    offset_x: usize,
+    width: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(ptr: *const T, base_width: usize, offset_x: usize, width: usize, range: Range<usize>) -> Self {
+        Iter {
+            ptr,
+            base_width,
+            offset_x,
+            width,
+            range,
+            lifetime: Default::default(),
+        }
+    }
+}
+
+impl<'a, T: 'a> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let y = self.range.start / self.width;
+        let x = self.range.start % self.width;
+        self.range.start += 1;
+        Some(unsafe { &*self.ptr.add(self.base_width * y + self.offset_x + x) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        let y = self.range.end / self.width;
+        let x = self.range.end % self.width;
+        Some(unsafe { &*self.ptr.add(self.base_width * y + self.offset_x + x) })
+    }
+}
+
+impl<'a, T: 'a> Producer for Iter<'a, T>
+where
+    Self: Send,
+{
+    type Item = &'a T;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let Iter {
+            ptr,
+            base_width,
+            offset_x,
+            width,
+            range,
+            ..
+        } = self;
+        (
+            Iter {
+                ptr,
+                base_width,
+                offset_x,
+                width,
+                range: range.start..index,
+                lifetime: Default::default(),
+            },
+            Iter {
+                ptr,
+                base_width,
+                offset_x,
+                width,
+                range: index..range.end,
+                lifetime: Default::default(),
+            },
+        )
+    }
+}
+
+impl<'a, T: 'a + Sync> ParallelIterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Sync> IndexedParallelIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct IterMut<'a, T> {
+    ptr: *mut T,
+    base_width: usize,
+    offset_x: usize,
+    width: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a mut ()>,
+}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+
+unsafe impl<'a, T: Send> Sync for IterMut<'a, T> {}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn new(ptr: *mut T, base_width: usize, offset_x: usize, width: usize, range: Range<usize>) -> Self {
+        IterMut {
+            ptr,
+            base_width,
+            offset_x,
+            width,
+            range,
+            lifetime: Default::default(),
+        }
+    }
+}
+
+impl<'a, T: 'a> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let y = self.range.start / self.width;
+        let x = self.range.start % self.width;
+        self.range.start += 1;
+        Some(unsafe { &mut *self.ptr.add(self.base_width * y + self.offset_x + x) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for IterMut<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        let y = self.range.end / self.width;
+        let x = self.range.end % self.width;
+        Some(unsafe { &mut *self.ptr.add(self.base_width * y + self.offset_x + x) })
+    }
+}
+
+impl<'a, T: 'a> Producer for IterMut<'a, T>
+where
+    Self: Send,
+{
+    type Item = &'a mut T;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let IterMut {
+            ptr,
+            base_width,
+            offset_x,
+            width,
+            range,
+            ..
+        } = self;
+        (
+            IterMut {
+                ptr,
+                base_width,
+                offset_x,
+                width,
+                range: range.start..index,
+                lifetime: Default::default(),
+            },
+            IterMut {
+                ptr,
+                base_width,
+                offset_x,
+                width,
+                range: index..range.end,
+                lifetime: Default::default(),
+            },
+        )
+    }
+}
+
+impl<'a, T: 'a + Send> ParallelIterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Send> IndexedParallelIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct IterOverhang<I> {
+    iter: I,
+    iter_width: usize,
+    iter_height: usize,
+    offset_x: usize,
+    offset_y: usize,
+    width: usize,
+    height: usize,
+    range: Range<usize>,
+}
+
+impl<I> IterOverhang<I> {
+    pub(crate) fn new(iter: I, iter_width: usize, iter_height: usize, offset_x: usize, offset_y: usize, width: usize, height: usize) -> Self {
+        IterOverhang {
+            iter,
+            iter_width,
+            iter_height,
+            offset_x,
+            offset_y,
+            width,
+            height,
+            range: 0..width * height,
+        }
+    }
+}
+
+impl<I: ExactSizeIterator + DoubleEndedIterator> Iterator for IterOverhang<I> {
+    type Item = Option<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let y = self.range.start / self.width;
+        let x = self.range.start % self.width;
+        self.range.start += 1;
+        if self.offset_x <= x && x < self.offset_x + self.iter_width && self.offset_y <= y && y < self.offset_y + self.iter_height {
+            Some(self.iter.next())
+        } else {
+            Some(None)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<I: ExactSizeIterator + DoubleEndedIterator> ExactSizeIterator for IterOverhang<I> {}
+
+impl<I: ExactSizeIterator + DoubleEndedIterator> DoubleEndedIterator for IterOverhang<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        let y = self.range.end / self.width;
+        let x = self.range.end % self.width;
+        if self.offset_x <= x && x < self.offset_x + self.iter_width && self.offset_y <= y && y < self.offset_y + self.iter_height {
+            Some(self.iter.next_back())
+        } else {
+            Some(None)
+        }
+    }
+}
+
+impl<I> IterOverhang<I> {
+    fn count_in_rect(&self, range: Range<usize>) -> usize {
+        self.count_in_rect_from_0(range.end) - self.count_in_rect_from_0(range.start)
+    }
+    fn count_in_rect_from_0(&self, to: usize) -> usize {
+        if self.iter_width == 0 || self.iter_height == 0 || to <= self.offset_y * self.width + self.offset_x {
+            return 0;
+        }
+        if (self.offset_y + self.iter_height - 1) * self.width + self.offset_x + self.iter_width - 1 < to {
+            return self.iter_width * self.iter_height;
+        }
+        let to_x = to % self.width;
+        let to_y = to / self.width;
+        match (to_x <= self.offset_x, self.offset_x + self.iter_width - 1 < to_x) {
+            (true, false) => (to_y - self.offset_y) * self.iter_width,
+            (false, true) => (to_y - self.offset_y + 1) * self.iter_width,
+            (false, false) => (to_y - self.offset_y) * self.iter_width + to_x - self.offset_x,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> Producer for IterOverhang<I>
+where
+    <I as Iterator>::Item: Send,
+{
+    type Item = Option<<I as Iterator>::Item>;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = index + self.range.start;
+        let iter_split_index = self.count_in_rect(self.range.start..index);
+        let IterOverhang {
+            iter,
+            iter_width,
+            iter_height,
+            offset_x,
+            offset_y,
+            width,
+            height,
+            range,
+        } = self;
+        let (left, right) = iter.split_at(iter_split_index);
+        (
+            IterOverhang {
+                iter: left,
+                iter_width,
+                iter_height,
+                offset_x,
+                offset_y,
+                width,
+                height,
+                range: range.start..index,
+            },
+            IterOverhang {
+                iter: right,
+                iter_width,
+                iter_height,
+                offset_x,
+                offset_y,
+                width,
+                height,
+                range: index..range.end,
+            },
+        )
+    }
+}
+
+impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> ParallelIterator for IterOverhang<I>
+where
+    <I as Iterator>::Item: Send,
+{
+    type Item = Option<<I as Iterator>::Item>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> IndexedParallelIterator for IterOverhang<I>
+where
+    <I as Iterator>::Item: Send,
+{
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct IterBorder<'a, T> {
+    iter: IterOverhang<Iter<'a, T>>,
+    source: ImageRef<'a, T, usize, usize>,
+    source_x: isize,
+    source_y: isize,
+    border: &'a Border<T>,
+}
+
+impl<'a, T> IterBorder<'a, T> {
+    pub(crate) fn new(iter: IterOverhang<Iter<'a, T>>, source: ImageRef<'a, T, usize, usize>, source_x: isize, source_y: isize, border: &'a Border<T>) -> Self {
+        IterBorder {
+            iter,
+            source,
+            source_x,
+            source_y,
+            border,
+        }
+    }
+
+    fn resolve(&self, index: usize) -> &'a T {
+        let x = (index % self.iter.width) as isize + self.source_x;
+        let y = (index / self.iter.width) as isize + self.source_y;
+        resolve_border(&self.source, x, y, self.border)
+    }
+}
+
+impl<'a, T: 'a> Iterator for IterBorder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.iter.range.start;
+        match self.iter.next()? {
+            Some(pixel) => Some(pixel),
+            None => Some(self.resolve(index)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for IterBorder<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for IterBorder<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.iter.next_back()? {
+            Some(pixel) => Some(pixel),
+            None => Some(self.resolve(self.iter.range.end)),
+        }
+    }
+}
+
+impl<'a, T: 'a + Sync> Producer for IterBorder<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let IterBorder {
+            iter,
+            source,
+            source_x,
+            source_y,
+            border,
+        } = self;
+        let (left, right) = iter.split_at(index);
+        (
+            IterBorder {
+                iter: left,
+                source,
+                source_x,
+                source_y,
+                border,
+            },
+            IterBorder {
+                iter: right,
+                source,
+                source_x,
+                source_y,
+                border,
+            },
+        )
+    }
+}
+
+impl<'a, T: 'a + Sync> ParallelIterator for IterBorder<'a, T> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Sync> IndexedParallelIterator for IterBorder<'a, T> {
+    fn len(&self) -> usize {
+        self.iter.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct IterWindow<'a, T, KW: MayBeConst<usize>, KH: MayBeConst<usize>> {
+    image: ImageRef<'a, T, usize, usize>,
+    kernel_width: KW,
+    kernel_height: KH,
+    range: Range<usize>,
+}
+
+unsafe impl<'a, T: Sync, KW: MayBeConst<usize>, KH: MayBeConst<usize>> Send for IterWindow<'a, T, KW, KH> {}
+
+unsafe impl<'a, T: Sync, KW: MayBeConst<usize>, KH: MayBeConst<usize>> Sync for IterWindow<'a, T, KW, KH> {}
+
+impl<'a, T, KW: MayBeConst<usize>, KH: MayBeConst<usize>> IterWindow<'a, T, KW, KH> {
+    pub(crate) fn new(image: ImageRef<'a, T, usize, usize>, kernel_width: KW, kernel_height: KH, range: Range<usize>) -> Self {
+        IterWindow {
+            image,
+            kernel_width,
+            kernel_height,
+            range,
+        }
+    }
+
+    fn window(&self, index: usize) -> ImageRefOverhang<'a, T, KW, KH> {
+        let x = (index % self.image.roi_width) as isize - (self.kernel_width.value() / 2) as isize;
+        let y = (index / self.image.roi_width) as isize - (self.kernel_height.value() / 2) as isize;
+        self.image.overhang(x, y, self.kernel_width, self.kernel_height)
+    }
+}
+
+impl<'a, T: 'a, KW: MayBeConst<usize>, KH: MayBeConst<usize>> Iterator for IterWindow<'a, T, KW, KH> {
+    type Item = ImageRefOverhang<'a, T, KW, KH>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let index = self.range.start;
+        self.range.start += 1;
+        Some(self.window(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a, KW: MayBeConst<usize>, KH: MayBeConst<usize>> ExactSizeIterator for IterWindow<'a, T, KW, KH> {}
+
+impl<'a, T: 'a, KW: MayBeConst<usize>, KH: MayBeConst<usize>> DoubleEndedIterator for IterWindow<'a, T, KW, KH> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.window(self.range.end))
+    }
+}
+
+impl<'a, T: 'a + Sync, KW: MayBeConst<usize>, KH: MayBeConst<usize>> Producer for IterWindow<'a, T, KW, KH> {
+    type Item = ImageRefOverhang<'a, T, KW, KH>;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let IterWindow {
+            image,
+            kernel_width,
+            kernel_height,
+            range,
+        } = self;
+        (
+            IterWindow {
+                image,
+                kernel_width,
+                kernel_height,
+                range: range.start..index,
+            },
+            IterWindow {
+                image,
+                kernel_width,
+                kernel_height,
+                range: index..range.end,
+            },
+        )
+    }
+}
+
+impl<'a, T: 'a + Sync, KW: MayBeConst<usize>, KH: MayBeConst<usize>> ParallelIterator for IterWindow<'a, T, KW, KH> {
+    type Item = ImageRefOverhang<'a, T, KW, KH>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Sync, KW: MayBeConst<usize>, KH: MayBeConst<usize>> IndexedParallelIterator for IterWindow<'a, T, KW, KH> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct TilesMut<'a, T> {
+    ptr: *mut T,
+    base_width: usize,
+    roi_x: usize,
+    roi_y: usize,
+    roi_width: usize,
+    roi_height: usize,
+    tile_width: usize,
+    tile_height: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a mut ()>,
+}
+
+unsafe impl<'a, T: Send> Send for TilesMut<'a, T> {}
+
+unsafe impl<'a, T: Send> Sync for TilesMut<'a, T> {}
+
+impl<'a, T> TilesMut<'a, T> {
+    pub(crate) fn new(image: ImageRefMut<'a, T, usize, usize>, tile_width: usize, tile_height: usize) -> Self {
+        assert!(tile_width > 0 && tile_height > 0, "size of tiles must not be 0");
+        let ImageRefMut {
+            base_width,
+            ptr,
+            roi_x,
+            roi_y,
+            roi_width,
+            roi_height,
+            ..
+        } = image;
+        TilesMut {
+            ptr,
+            base_width,
+            roi_x,
+            roi_y,
+            roi_width,
+            roi_height,
+            tile_width,
+            tile_height,
+            range: 0..Self::count(roi_width, tile_width) * Self::count(roi_height, tile_height),
+            lifetime: Default::default(),
+        }
+    }
+
+    pub(crate) fn count(length: usize, tile_length: usize) -> usize {
+        length / tile_length + (length % tile_length != 0) as usize
+    }
+
+    fn tile(&self, index: usize) -> ImageRefMut<'a, T, usize, usize> {
+        let tiles_x = Self::count(self.roi_width, self.tile_width);
+        let x = index % tiles_x * self.tile_width;
+        let y = index / tiles_x * self.tile_height;
+        let width = self.tile_width.min(self.roi_width - x);
+        let height = self.tile_height.min(self.roi_height - y);
+        ImageRefMut::new(self.base_width, self.ptr, self.roi_x + x, self.roi_y + y, width, height)
+    }
+}
+
+impl<'a, T: 'a> Iterator for TilesMut<'a, T> {
+    type Item = ImageRefMut<'a, T, usize, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let index = self.range.start;
+        self.range.start += 1;
+        Some(self.tile(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for TilesMut<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for TilesMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.tile(self.range.end))
+    }
+}
+
+impl<'a, T: 'a + Send + Sync> Producer for TilesMut<'a, T> {
+    type Item = ImageRefMut<'a, T, usize, usize>;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let TilesMut { range, .. } = self;
+        (TilesMut { range: range.start..index, ..self }, TilesMut { range: index..range.end, ..self })
+    }
+}
+
+impl<'a, T: 'a + Send + Sync> ParallelIterator for TilesMut<'a, T> {
+    type Item = ImageRefMut<'a, T, usize, usize>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Send + Sync> IndexedParallelIterator for TilesMut<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct Rows<'a, T> {
+    ptr: *const T,
+    base_width: usize,
+    roi_x: usize,
+    roi_y: usize,
+    width: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, T: Sync> Send for Rows<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Rows<'a, T> {}
+
+impl<'a, T> Rows<'a, T> {
+    pub(crate) fn new(ptr: *const T, base_width: usize, roi_x: usize, roi_y: usize, width: usize, range: Range<usize>) -> Self {
+        Rows {
+            ptr,
+            base_width,
+            roi_x,
+            roi_y,
+            width,
+            range,
+            lifetime: Default::default(),
+        }
+    }
+
+    fn item(&self, index: usize) -> (usize, &'a [T]) {
+        (index, unsafe {
+            std::slice::from_raw_parts(self.ptr.add((self.roi_y + index) * self.base_width + self.roi_x), self.width)
+        })
+    }
+}
+
+impl<'a, T: 'a> Iterator for Rows<'a, T> {
+    type Item = (usize, &'a [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let index = self.range.start;
+        self.range.start += 1;
+        Some(self.item(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Rows<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for Rows<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.item(self.range.end))
+    }
+}
+
+impl<'a, T: 'a + Sync> Producer for Rows<'a, T> {
+    type Item = (usize, &'a [T]);
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let Rows { range, .. } = self;
+        (Rows { range: range.start..index, ..self }, Rows { range: index..range.end, ..self })
+    }
+}
+
+impl<'a, T: 'a + Sync> ParallelIterator for Rows<'a, T> {
+    type Item = (usize, &'a [T]);
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Sync> IndexedParallelIterator for Rows<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct RowsMut<'a, T> {
+    ptr: *mut T,
+    base_width: usize,
+    roi_x: usize,
+    roi_y: usize,
+    width: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a mut ()>,
+}
+
+unsafe impl<'a, T: Send> Send for RowsMut<'a, T> {}
+
+unsafe impl<'a, T: Send> Sync for RowsMut<'a, T> {}
+
+impl<'a, T> RowsMut<'a, T> {
+    pub(crate) fn new(ptr: *mut T, base_width: usize, roi_x: usize, roi_y: usize, width: usize, range: Range<usize>) -> Self {
+        RowsMut {
+            ptr,
+            base_width,
+            roi_x,
+            roi_y,
+            width,
+            range,
+            lifetime: Default::default(),
+        }
+    }
+
+    fn item(&self, index: usize) -> (usize, &'a mut [T]) {
+        (index, unsafe {
+            std::slice::from_raw_parts_mut(self.ptr.add((self.roi_y + index) * self.base_width + self.roi_x), self.width)
+        })
+    }
+}
+
+impl<'a, T: 'a> Iterator for RowsMut<'a, T> {
+    type Item = (usize, &'a mut [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let index = self.range.start;
+        self.range.start += 1;
+        Some(self.item(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for RowsMut<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for RowsMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.item(self.range.end))
+    }
+}
+
+impl<'a, T: 'a + Send> Producer for RowsMut<'a, T> {
+    type Item = (usize, &'a mut [T]);
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let RowsMut { range, .. } = self;
+        (RowsMut { range: range.start..index, ..self }, RowsMut { range: index..range.end, ..self })
+    }
+}
+
+impl<'a, T: 'a + Send> ParallelIterator for RowsMut<'a, T> {
+    type Item = (usize, &'a mut [T]);
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Send> IndexedParallelIterator for RowsMut<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct Columns<'a, T> {
+    ptr: *const T,
+    base_width: usize,
+    roi_x: usize,
+    roi_y: usize,
+    height: usize,
+    range: Range<usize>,
+    lifetime: PhantomData<&'a ()>,
+}
+
+unsafe impl<'a, T: Sync> Send for Columns<'a, T> {}
+
+unsafe impl<'a, T: Sync> Sync for Columns<'a, T> {}
+
+impl<'a, T> Columns<'a, T> {
+    pub(crate) fn new(ptr: *const T, base_width: usize, roi_x: usize, roi_y: usize, height: usize, range: Range<usize>) -> Self {
+        Columns {
+            ptr,
+            base_width,
+            roi_x,
+            roi_y,
+            height,
+            range,
+            lifetime: Default::default(),
+        }
+    }
+
+    fn item(&self, index: usize) -> (usize, ImageRef<'a, T, ConstUsize<1>, usize>) {
+        (index, ImageRef::new(self.base_width, self.ptr, self.roi_x + index, self.roi_y, ConstUsize::<1>::new(), self.height))
+    }
+}
+
+impl<'a, T: 'a> Iterator for Columns<'a, T> {
+    type Item = (usize, ImageRef<'a, T, ConstUsize<1>, usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let index = self.range.start;
+        self.range.start += 1;
+        Some(self.item(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.range.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Columns<'a, T> {}
+
+impl<'a, T: 'a> DoubleEndedIterator for Columns<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        self.range.end -= 1;
+        Some(self.item(self.range.end))
+    }
+}
+
+impl<'a, T: 'a + Sync> Producer for Columns<'a, T> {
+    type Item = (usize, ImageRef<'a, T, ConstUsize<1>, usize>);
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let index = self.range.start + index;
+        let Columns { range, .. } = self;
+        (Columns { range: range.start..index, ..self }, Columns { range: index..range.end, ..self })
+    }
+}
+
+impl<'a, T: 'a + Sync> ParallelIterator for Columns<'a, T> {
+    type Item = (usize, ImageRef<'a, T, ConstUsize<1>, usize>);
+
+    fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+}
+
+impl<'a, T: 'a + Sync> IndexedParallelIterator for Columns<'a, T> {
+    fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> <CB as ProducerCallback<Self::Item>>::Output {
+        callback.callback(self)
+    }
+}
+
+pub struct ColumnsMut<'a, T> {
+    ptr: *mut T,
+    base_width: usize,
+    roi_x: usize,
+    roi_y: usize,
+    height: usize,
     range: Range<usize>,
-    lifetime: PhantomData<&'a ()>,
+    lifetime: PhantomData<&'a mut ()>,
 }
 
-unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Send + Sync> Send for ColumnsMut<'a, T> {}
 
-unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+unsafe impl<'a, T: Send + Sync> Sync for ColumnsMut<'a, T> {}
 
-impl<'a, T> Iter<'a, T> {
-    pub(crate) fn new(ptr: *const T, base_width: usize, offset_x: usize, width: usize, range: Range<usize>) -> Self {
-        Iter {
+impl<'a, T> ColumnsMut<'a, T> {
+    pub(crate) fn new(ptr: *mut T, base_width: usize, roi_x: usize, roi_y: usize, height: usize, range: Range<usize>) -> Self {
+        ColumnsMut {
             ptr,
             base_width,
-            offset_x,
-            width,
+            roi_x,
+            roi_y,
+            height,
             range,
             lifetime: Default::default(),
         }
     }
+
+    fn item(&self, index: usize) -> (usize, ImageRefMut<'a, T, ConstUsize<1>, usize>) {
+        (index, ImageRefMut::new(self.base_width, self.ptr, self.roi_x + index, self.roi_y, ConstUsize::<1>::new(), self.height))
+    }
 }
 
-impl<'a, T: 'a> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+impl<'a, T: 'a> Iterator for ColumnsMut<'a, T> {
+    type Item = (usize, ImageRefMut<'a, T, ConstUsize<1>, usize>);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
-        let y = self.range.start / self.width;
-        let x = self.range.start % self.width;
+        let index = self.range.start;
         self.range.start += 1;
-        Some(unsafe { &*self.ptr.add(self.base_width * y + self.offset_x + x) })
+        Some(self.item(index))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -49,25 +1147,20 @@ impl<'a, T: 'a> Iterator for Iter<'a, T> {
     }
 }
 
-impl<'a, T: 'a> ExactSizeIterator for Iter<'a, T> {}
+impl<'a, T: 'a> ExactSizeIterator for ColumnsMut<'a, T> {}
 
-impl<'a, T: 'a> DoubleEndedIterator for Iter<'a, T> {
+impl<'a, T: 'a> DoubleEndedIterator for ColumnsMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
         self.range.end -= 1;
-        let y = self.range.end / self.width;
-        let x = self.range.end % self.width;
-        Some(unsafe { &*self.ptr.add(self.base_width * y + self.offset_x + x) })
+        Some(self.item(self.range.end))
     }
 }
 
-impl<'a, T: 'a> Producer for Iter<'a, T>
-where
-    Self: Send,
-{
-    type Item = &'a T;
+impl<'a, T: 'a + Send + Sync> Producer for ColumnsMut<'a, T> {
+    type Item = (usize, ImageRefMut<'a, T, ConstUsize<1>, usize>);
     type IntoIter = Self;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -76,37 +1169,13 @@ where
 
     fn split_at(self, index: usize) -> (Self, Self) {
         let index = self.range.start + index;
-        let Iter {
-            ptr,
-            base_width,
-            offset_x,
-            width,
-            range,
-            ..
-        } = self;
-        (
-            Iter {
-                ptr,
-                base_width,
-                offset_x,
-                width,
-                range: range.start..index,
-                lifetime: Default::default(),
-            },
-            Iter {
-                ptr,
-                base_width,
-                offset_x,
-                width,
-                range: index..range.end,
-                lifetime: Default::default(),
-            },
-        )
+        let ColumnsMut { range, .. } = self;
+        (ColumnsMut { range: range.start..index, ..self }, ColumnsMut { range: index..range.end, ..self })
     }
 }
 
-impl<'a, T: 'a + Sync> ParallelIterator for Iter<'a, T> {
-    type Item = &'a T;
+impl<'a, T: 'a + Send + Sync> ParallelIterator for ColumnsMut<'a, T> {
+    type Item = (usize, ImageRefMut<'a, T, ConstUsize<1>, usize>);
 
     fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
     where
@@ -116,7 +1185,7 @@ impl<'a, T: 'a + Sync> ParallelIterator for Iter<'a, T> {
     }
 }
 
-impl<'a, T: 'a + Sync> IndexedParallelIterator for Iter<'a, T> {
+impl<'a, T: 'a + Send + Sync> IndexedParallelIterator for ColumnsMut<'a, T> {
     fn len(&self) -> usize {
         self.range.len()
     }
@@ -130,43 +1199,47 @@ impl<'a, T: 'a + Sync> IndexedParallelIterator for Iter<'a, T> {
     }
 }
 
-pub struct IterMut<'a, T> {
-    ptr: *mut T,
-    base_width: usize,
-    offset_x: usize,
+pub struct IterStrided<'a, T> {
+    ptr: *const T,
+    step_x: isize,
+    step_y: isize,
     width: usize,
     range: Range<usize>,
-    lifetime: PhantomData<&'a mut ()>,
+    lifetime: PhantomData<&'a ()>,
 }
 
-unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Send for IterStrided<'a, T> {}
 
-unsafe impl<'a, T: Send> Sync for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterStrided<'a, T> {}
 
-impl<'a, T> IterMut<'a, T> {
-    pub(crate) fn new(ptr: *mut T, base_width: usize, offset_x: usize, width: usize, range: Range<usize>) -> Self {
-        IterMut {
+impl<'a, T> IterStrided<'a, T> {
+    pub(crate) fn new(ptr: *const T, step_x: isize, step_y: isize, width: usize, range: Range<usize>) -> Self {
+        IterStrided {
             ptr,
-            base_width,
-            offset_x,
+            step_x,
+            step_y,
             width,
             range,
             lifetime: Default::default(),
         }
     }
+
+    fn item(&self, index: usize) -> &'a T {
+        let (x, y) = ((index % self.width) as isize, (index / self.width) as isize);
+        unsafe { &*self.ptr.offset(x * self.step_x + y * self.step_y) }
+    }
 }
 
-impl<'a, T: 'a> Iterator for IterMut<'a, T> {
-    type Item = &'a mut T;
+impl<'a, T: 'a> Iterator for IterStrided<'a, T> {
+    type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
-        let y = self.range.start / self.width;
-        let x = self.range.start % self.width;
+        let index = self.range.start;
         self.range.start += 1;
-        Some(unsafe { &mut *self.ptr.add(self.base_width * y + self.offset_x + x) })
+        Some(self.item(index))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -175,25 +1248,20 @@ impl<'a, T: 'a> Iterator for IterMut<'a, T> {
     }
 }
 
-impl<'a, T: 'a> ExactSizeIterator for IterMut<'a, T> {}
+impl<'a, T: 'a> ExactSizeIterator for IterStrided<'a, T> {}
 
-impl<'a, T: 'a> DoubleEndedIterator for IterMut<'a, T> {
+impl<'a, T: 'a> DoubleEndedIterator for IterStrided<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
         self.range.end -= 1;
-        let y = self.range.end / self.width;
-        let x = self.range.end % self.width;
-        Some(unsafe { &mut *self.ptr.add(self.base_width * y + self.offset_x + x) })
+        Some(self.item(self.range.end))
     }
 }
 
-impl<'a, T: 'a> Producer for IterMut<'a, T>
-where
-    Self: Send,
-{
-    type Item = &'a mut T;
+impl<'a, T: 'a + Sync> Producer for IterStrided<'a, T> {
+    type Item = &'a T;
     type IntoIter = Self;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -202,37 +1270,13 @@ where
 
     fn split_at(self, index: usize) -> (Self, Self) {
         let index = self.range.start + index;
-        let IterMut {
-            ptr,
-            base_width,
-            offset_x,
-            width,
-            range,
-            ..
-        } = self;
-        (
-            IterMut {
-                ptr,
-                base_width,
-                offset_x,
-                width,
-                range: range.start..index,
-                lifetime: Default::default(),
-            },
-            IterMut {
-                ptr,
-                base_width,
-                offset_x,
-                width,
-                range: index..range.end,
-                lifetime: Default::default(),
-            },
-        )
+        let IterStrided { range, .. } = self;
+        (IterStrided { range: range.start..index, ..self }, IterStrided { range: index..range.end, ..self })
     }
 }
 
-impl<'a, T: 'a + Send> ParallelIterator for IterMut<'a, T> {
-    type Item = &'a mut T;
+impl<'a, T: 'a + Sync> ParallelIterator for IterStrided<'a, T> {
+    type Item = &'a T;
 
     fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
     where
@@ -242,7 +1286,7 @@ impl<'a, T: 'a + Send> ParallelIterator for IterMut<'a, T> {
     }
 }
 
-impl<'a, T: 'a + Send> IndexedParallelIterator for IterMut<'a, T> {
+impl<'a, T: 'a + Sync> IndexedParallelIterator for IterStrided<'a, T> {
     fn len(&self) -> usize {
         self.range.len()
     }
@@ -256,47 +1300,47 @@ impl<'a, T: 'a + Send> IndexedParallelIterator for IterMut<'a, T> {
     }
 }
 
-pub struct IterOverhang<I> {
-    iter: I,
-    iter_width: usize,
-    iter_height: usize,
-    offset_x: usize,
-    offset_y: usize,
+pub struct IterStridedMut<'a, T> {
+    ptr: *mut T,
+    step_x: isize,
+    step_y: isize,
     width: usize,
-    height: usize,
     range: Range<usize>,
+    lifetime: PhantomData<&'a mut ()>,
 }
 
-impl<I> IterOverhang<I> {
-    pub(crate) fn new(iter: I, iter_width: usize, iter_height: usize, offset_x: usize, offset_y: usize, width: usize, height: usize) -> Self {
-        IterOverhang {
-            iter,
-            iter_width,
-            iter_height,
-            offset_x,
-            offset_y,
+unsafe impl<'a, T: Send> Send for IterStridedMut<'a, T> {}
+
+unsafe impl<'a, T: Send> Sync for IterStridedMut<'a, T> {}
+
+impl<'a, T> IterStridedMut<'a, T> {
+    pub(crate) fn new(ptr: *mut T, step_x: isize, step_y: isize, width: usize, range: Range<usize>) -> Self {
+        IterStridedMut {
+            ptr,
+            step_x,
+            step_y,
             width,
-            height,
-            range: 0..width * height,
+            range,
+            lifetime: Default::default(),
         }
     }
+
+    fn item(&self, index: usize) -> &'a mut T {
+        let (x, y) = ((index % self.width) as isize, (index / self.width) as isize);
+        unsafe { &mut *self.ptr.offset(x * self.step_x + y * self.step_y) }
+    }
 }
 
-impl<I: ExactSizeIterator + DoubleEndedIterator> Iterator for IterOverhang<I> {
-    type Item = Option<I::Item>;
+impl<'a, T: 'a> Iterator for IterStridedMut<'a, T> {
+    type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
-        let y = self.range.start / self.width;
-        let x = self.range.start % self.width;
+        let index = self.range.start;
         self.range.start += 1;
-        if self.offset_x <= x && x < self.offset_x + self.iter_width && self.offset_y <= y && y < self.offset_y + self.iter_height {
-            Some(self.iter.next())
-        } else {
-            Some(None)
-        }
+        Some(self.item(index))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -305,51 +1349,20 @@ impl<I: ExactSizeIterator + DoubleEndedIterator> Iterator for IterOverhang<I> {
     }
 }
 
-impl<I: ExactSizeIterator + DoubleEndedIterator> ExactSizeIterator for IterOverhang<I> {}
+impl<'a, T: 'a> ExactSizeIterator for IterStridedMut<'a, T> {}
 
-impl<I: ExactSizeIterator + DoubleEndedIterator> DoubleEndedIterator for IterOverhang<I> {
+impl<'a, T: 'a> DoubleEndedIterator for IterStridedMut<'a, T> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.range.is_empty() {
             return None;
         }
         self.range.end -= 1;
-        let y = self.range.end / self.width;
-        let x = self.range.end % self.width;
-        if self.offset_x <= x && x < self.offset_x + self.iter_width && self.offset_y <= y && y < self.offset_y + self.iter_height {
-            Some(self.iter.next_back())
-        } else {
-            Some(None)
-        }
-    }
-}
-
-impl<I> IterOverhang<I> {
-    fn count_in_rect(&self, range: Range<usize>) -> usize {
-        self.count_in_rect_from_0(range.end) - self.count_in_rect_from_0(range.start)
-    }
-    fn count_in_rect_from_0(&self, to: usize) -> usize {
-        if to <= self.offset_y * self.width + self.offset_x {
-            return 0;
-        }
-        if (self.offset_y + self.iter_height - 1) * self.width + self.offset_x + self.iter_width - 1 < to {
-            return self.iter_width * self.iter_height;
-        }
-        let to_x = to % self.width;
-        let to_y = to / self.width;
-        match (to_x <= self.offset_x, self.offset_x + self.iter_width - 1 < to_x) {
-            (true, false) => (to_y - self.offset_y) * self.iter_width,
-            (false, true) => (to_y - self.offset_y + 1) * self.iter_width,
-            (false, false) => (to_y - self.offset_y) * self.iter_width + to_x - self.offset_x,
-            _ => unreachable!(),
-        }
+        Some(self.item(self.range.end))
     }
 }
 
-impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> Producer for IterOverhang<I>
-where
-    <I as Iterator>::Item: Send,
-{
-    type Item = Option<<I as Iterator>::Item>;
+impl<'a, T: 'a + Send> Producer for IterStridedMut<'a, T> {
+    type Item = &'a mut T;
     type IntoIter = Self;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -357,49 +1370,14 @@ where
     }
 
     fn split_at(self, index: usize) -> (Self, Self) {
-        let index = index + self.range.start;
-        let iter_split_index = self.count_in_rect(self.range.start..index);
-        let IterOverhang {
-            iter,
-            iter_width,
-            iter_height,
-            offset_x,
-            offset_y,
-            width,
-            height,
-            range,
-        } = self;
-        let (left, right) = iter.split_at(iter_split_index);
-        (
-            IterOverhang {
-                iter: left,
-                iter_width,
-                iter_height,
-                offset_x,
-                offset_y,
-                width,
-                height,
-                range: range.start..index,
-            },
-            IterOverhang {
-                iter: right,
-                iter_width,
-                iter_height,
-                offset_x,
-                offset_y,
-                width,
-                height,
-                range: index..range.end,
-            },
-        )
+        let index = self.range.start + index;
+        let IterStridedMut { range, .. } = self;
+        (IterStridedMut { range: range.start..index, ..self }, IterStridedMut { range: index..range.end, ..self })
     }
 }
 
-impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> ParallelIterator for IterOverhang<I>
-where
-    <I as Iterator>::Item: Send,
-{
-    type Item = Option<<I as Iterator>::Item>;
+impl<'a, T: 'a + Send> ParallelIterator for IterStridedMut<'a, T> {
+    type Item = &'a mut T;
 
     fn drive_unindexed<C>(self, consumer: C) -> <C as Consumer<Self::Item>>::Result
     where
@@ -409,10 +1387,7 @@ where
     }
 }
 
-impl<I: Producer<Item = <I as Iterator>::Item, IntoIter = I> + ExactSizeIterator + DoubleEndedIterator> IndexedParallelIterator for IterOverhang<I>
-where
-    <I as Iterator>::Item: Send,
-{
+impl<'a, T: 'a + Send> IndexedParallelIterator for IterStridedMut<'a, T> {
     fn len(&self) -> usize {
         self.range.len()
     }
@@ -430,6 +1405,7 @@ where
 mod tests {
     use rayon::iter::plumbing::Producer;
 
+    use crate::border::Border;
     use crate::image_ref::iter::IterOverhang;
     use crate::physical_image::PhysicalImage;
     use crate::{IntoPixelIterator, IntoSerializedPixelIterator, View, ViewMut, WritePixel};
@@ -953,4 +1929,27 @@ mod tests {
         let view = view.view_overhang_mut(-10, -10, 30, 30);
         equals_recurrent_mut_back(view.into_pix_iter().into_inner(), &mut vec);
     }
+
+    #[test]
+    fn iter_border_split() {
+        const HEIGHT: usize = 10;
+        const WIDTH: usize = 10;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = y * WIDTH + x;
+            }
+        }
+        let border = Border::Reflect101;
+        let view = image.view_overhang(-3, 5, 16, 8);
+        let expect = view.pix_iter_serialized_border(&border).into_inner().copied().collect::<Vec<_>>();
+        assert_eq!(expect.len(), 16 * 8);
+        for begin in 0..expect.len() {
+            for end in begin..expect.len() {
+                let (_, right) = view.pix_iter_border(&border).into_inner().split_at(begin);
+                let (middle, _) = right.split_at(end - begin);
+                assert_eq!(Producer::into_iter(middle).copied().collect::<Vec<_>>(), &expect[begin..end]);
+            }
+        }
+    }
 }
@@ -0,0 +1,186 @@
+//! Integral images for constant-time sums over rectangles.
+
+use std::ops::{Add, Mul, Sub};
+
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
+
+use crate::physical_image::PhysicalImage;
+use crate::{ReadPixel, Rectangle};
+
+/// A type which can be accumulated in IntegralImage.
+pub trait IntegralValue: Copy + Default + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    /// The widest type of the same kind, in which squares are accumulated.
+    type Wide: IntegralValue;
+    /// Convert into Self::Wide.
+    fn widen(self) -> Self::Wide;
+    /// Convert into f64 for means and variances.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_integral_value {
+    ($($t:ty => $wide:ty),*) => {
+        $(
+            impl IntegralValue for $t {
+                type Wide = $wide;
+
+                fn widen(self) -> $wide {
+                    self as $wide
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_integral_value!(i32 => i128, i64 => i128, i128 => i128, u32 => u128, u64 => u128, u128 => u128, f32 => f64, f64 => f64);
+
+/// Add every row of table to the next one, turning row prefix sums into a summed-area table.
+fn accumulate_rows<A: IntegralValue>(table: &mut PhysicalImage<A>) {
+    let mut rows = table.rows_mut().map(|(_, row)| row);
+    if let Some(mut previous) = rows.next() {
+        for row in rows {
+            row.iter_mut().zip(previous.iter()).for_each(|(value, &above)| *value = *value + above);
+            previous = row;
+        }
+    }
+}
+
+/// A summed-area table, which answers sums, means and variances of any rectangle in O(1).
+#[derive(Debug)]
+pub struct IntegralImage<A: IntegralValue = f64> {
+    sums: PhysicalImage<A>,
+    square_sums: PhysicalImage<A::Wide>,
+}
+
+impl<A: IntegralValue> IntegralImage<A> {
+    /// Build the table of source, row prefix sums in parallel first and then sums down the columns.
+    /// Pixels outside of valid area of source count as zero.
+    /// Sums are accumulated in A and sums of squares in A::Wide, so A must hold the sum of all pixels:
+    /// u32 overflows beyond about 16 million 8-bit pixels, while u64 holds sums of about 4 billion 32-bit pixels.
+    /// Squares of 64-bit and 128-bit pixels may still overflow A::Wide.
+    pub fn new<S>(source: &S) -> Self
+    where
+        S: ReadPixel + Sync,
+        S::Item: Copy + Into<A>,
+    {
+        let (width, height) = (source.width(), source.height());
+        let mut sums = PhysicalImage::from_fn(width + 1, height + 1, |_, _| A::default());
+        let mut square_sums = PhysicalImage::from_fn(width + 1, height + 1, |_, _| A::Wide::default());
+        sums.par_rows_mut().zip(square_sums.par_rows_mut()).skip(1).for_each(|((y, sum), (_, square_sum))| {
+            for x in 0..width {
+                let value: A = source.get(x, y - 1).map_or_else(A::default, |value| (*value).into());
+                sum[x + 1] = sum[x] + value;
+                square_sum[x + 1] = square_sum[x] + value.widen() * value.widen();
+            }
+        });
+        rayon::join(|| accumulate_rows(&mut sums), || accumulate_rows(&mut square_sums));
+        IntegralImage { sums, square_sums }
+    }
+
+    /// Get width of the source image.
+    pub fn width(&self) -> usize {
+        self.sums.width() - 1
+    }
+
+    /// Get height of the source image.
+    pub fn height(&self) -> usize {
+        self.sums.height() - 1
+    }
+
+    fn rect_sum<V: IntegralValue>(table: &PhysicalImage<V>, rect: &Rectangle) -> V {
+        let (x0, y0, x1, y1) = (rect.x, rect.y, rect.x + rect.w, rect.y + rect.h);
+        unsafe { *table.get_unchecked(x1, y1) + *table.get_unchecked(x0, y0) - *table.get_unchecked(x1, y0) - *table.get_unchecked(x0, y1) }
+    }
+
+    fn contains(&self, rect: &Rectangle) -> bool {
+        rect.x + rect.w <= self.width() && rect.y + rect.h <= self.height()
+    }
+
+    /// Get the sum of pixels in rect.
+    /// If rect is not included in the source image, this function returns None.
+    pub fn sum(&self, rect: &Rectangle) -> Option<A> {
+        if self.contains(rect) {
+            Some(Self::rect_sum(&self.sums, rect))
+        } else {
+            None
+        }
+    }
+
+    /// Get the sum of squared pixels in rect.
+    /// If rect is not included in the source image, this function returns None.
+    pub fn square_sum(&self, rect: &Rectangle) -> Option<A::Wide> {
+        if self.contains(rect) {
+            Some(Self::rect_sum(&self.square_sums, rect))
+        } else {
+            None
+        }
+    }
+
+    /// Get the mean of pixels in rect.
+    /// If rect is empty or not included in the source image, this function returns None.
+    pub fn mean(&self, rect: &Rectangle) -> Option<f64> {
+        if rect.w * rect.h == 0 {
+            return None;
+        }
+        Some(self.sum(rect)?.to_f64() / (rect.w * rect.h) as f64)
+    }
+
+    /// Get the population variance of pixels in rect.
+    /// If rect is empty or not included in the source image, this function returns None.
+    pub fn variance(&self, rect: &Rectangle) -> Option<f64> {
+        let mean = self.mean(rect)?;
+        let square_mean = Self::rect_sum(&self.square_sums, rect).to_f64() / (rect.w * rect.h) as f64;
+        Some((square_mean - mean * mean).max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::integral::IntegralImage;
+    use crate::physical_image::PhysicalImage;
+    use crate::{ReadPixel, Rectangle, View};
+
+    #[test]
+    fn integral() {
+        const WIDTH: usize = 37;
+        const HEIGHT: usize = 23;
+        let image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| ((x * 7 + y * 13) % 256) as u8);
+        let integral = IntegralImage::<u64>::new(&image);
+        assert_eq!((integral.width(), integral.height()), (WIDTH, HEIGHT));
+        for &(x, y, w, h) in &[(0, 0, WIDTH, HEIGHT), (3, 5, 10, 7), (36, 22, 1, 1), (10, 0, 0, 5), (0, 4, WIDTH, 1)] {
+            let rect = Rectangle { x, y, w, h };
+            let values = (y..y + h)
+                .flat_map(|y| (x..x + w).map(move |x| (x, y)))
+                .map(|(x, y)| *image.get(x, y).unwrap() as u64)
+                .collect::<Vec<_>>();
+            assert_eq!(integral.sum(&rect), Some(values.iter().sum::<u64>()));
+            assert_eq!(integral.square_sum(&rect), Some(values.iter().map(|&v| v as u128 * v as u128).sum::<u128>()));
+            if values.is_empty() {
+                assert_eq!(integral.mean(&rect), None);
+                continue;
+            }
+            let mean = values.iter().sum::<u64>() as f64 / values.len() as f64;
+            let variance = values.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / values.len() as f64;
+            assert!((integral.mean(&rect).unwrap() - mean).abs() < 1e-9);
+            assert!((integral.variance(&rect).unwrap() - variance).abs() < 1e-6);
+        }
+        assert_eq!(integral.sum(&Rectangle { x: 30, y: 0, w: 8, h: 1 }), None);
+        assert_eq!(integral.variance(&Rectangle { x: 0, y: 20, w: 1, h: 4 }), None);
+
+        let view = image.view_overhang(-2, -1, 5, 4);
+        let integral = IntegralImage::<f64>::new(&view);
+        let expect = (0..3).map(|y| (0..3).map(|x| *image.get(x, y).unwrap() as f64).sum::<f64>()).sum::<f64>();
+        assert_eq!(integral.sum(&Rectangle { x: 0, y: 0, w: 5, h: 4 }), Some(expect));
+
+        let bright = IntegralImage::<u32>::new(&PhysicalImage::from_fn(300, 300, |_, _| 255u8));
+        let all = Rectangle { x: 0, y: 0, w: 300, h: 300 };
+        assert_eq!((bright.sum(&all), bright.square_sum(&all)), (Some(22_950_000), Some(5_852_250_000)));
+        assert_eq!(bright.variance(&all), Some(0.0));
+
+        let empty = IntegralImage::<f64>::new(&PhysicalImage::<f32>::new(0, 0));
+        assert_eq!(empty.sum(&Rectangle { x: 0, y: 0, w: 0, h: 0 }), Some(0.0));
+    }
+}
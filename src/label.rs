@@ -0,0 +1,158 @@
+//! Labeling of connected components.
+
+use partial_const::MayBeConst;
+use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator, ParallelSliceMut};
+
+use crate::physical_image::PhysicalImage;
+use crate::{Connectivity, ReadPixel};
+
+/// Number of rows labeled by a task before bands are merged.
+const BAND_HEIGHT: usize = 64;
+
+/// Find root of index in a union-find forest whose indices start at offset, halving paths on the way.
+fn find(parent: &mut [u32], offset: usize, mut index: usize) -> usize {
+    while parent[index - offset] as usize != index {
+        let grandparent = parent[parent[index - offset] as usize - offset];
+        parent[index - offset] = grandparent;
+        index = grandparent as usize;
+    }
+    index
+}
+
+/// Join trees of a and b by linking the larger root to the smaller one, so that every root is the first pixel of its tree.
+fn union(parent: &mut [u32], offset: usize, a: usize, b: usize) {
+    let (a, b) = (find(parent, offset, a), find(parent, offset, b));
+    if a < b {
+        parent[b - offset] = a as u32;
+    } else if b < a {
+        parent[a - offset] = b as u32;
+    }
+}
+
+/// Label connected components of pixels with equal values other than T::default() (false for binary images, 0 for label images).
+/// Background pixels are labeled 0 and components are labeled 1 to count in order of their first pixels in row-major order.
+/// Rows are labeled in parallel bands by union-find, and then trees across boundaries of bands are joined.
+/// # Panics
+/// Panics if the number of pixels exceeds u32::MAX.
+pub fn label_components<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> (PhysicalImage<u32, W, H>, u32)
+where
+    T: Copy + PartialEq + Default + Send + Sync,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let (width, height) = (image.width(), image.height());
+    let data = image.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>();
+    assert!(data.len() <= u32::MAX as usize, "too many pixels to label");
+    let background = T::default();
+    let connected = |i: usize, j: usize| data[i] != background && data[i] == data[j];
+    let band = (width * BAND_HEIGHT).max(1);
+    let mut parent = (0..data.len() as u32).collect::<Vec<_>>();
+    parent.par_chunks_mut(band).enumerate().for_each(|(index, parent)| {
+        let offset = index * band;
+        for i in offset..offset + parent.len() {
+            for &delta in connectivity.preceding() {
+                match Connectivity::neighbor(i, delta, width, height) {
+                    Some(j) if j >= offset && connected(i, j) => union(parent, offset, i, j),
+                    _ => {}
+                }
+            }
+        }
+    });
+    for i in (band..data.len()).step_by(band).flat_map(|start| start..start + width) {
+        for &delta in connectivity.preceding().iter().filter(|(_, dy)| *dy < 0) {
+            match Connectivity::neighbor(i, delta, width, height) {
+                Some(j) if connected(i, j) => union(&mut parent, 0, i, j),
+                _ => {}
+            }
+        }
+    }
+    let is_root = |i: usize| data[i] != background && parent[i] as usize == i;
+    let counts = (0..(data.len() + band - 1) / band)
+        .into_par_iter()
+        .map(|index| (index * band..data.len().min((index + 1) * band)).filter(|&i| is_root(i)).count() as u32)
+        .collect::<Vec<_>>();
+    let firsts = counts.iter().scan(0, |sum, &count| Some(std::mem::replace(sum, *sum + count))).collect::<Vec<_>>();
+    let mut root_labels = vec![0u32; data.len()];
+    root_labels.par_chunks_mut(band).zip(firsts.into_par_iter()).enumerate().for_each(|(index, (labels, mut label))| {
+        for (_, value) in (index * band..).zip(labels.iter_mut()).filter(|&(i, _)| is_root(i)) {
+            label += 1;
+            *value = label;
+        }
+    });
+    let labels = (0..data.len())
+        .into_par_iter()
+        .map(|i| {
+            if data[i] == background {
+                return 0;
+            }
+            let mut root = i;
+            while parent[root] as usize != root {
+                root = parent[root] as usize;
+            }
+            root_labels[root]
+        })
+        .collect();
+    (PhysicalImage::with_data(image.width, image.height, labels), counts.iter().sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::label::label_components;
+    use crate::physical_image::PhysicalImage;
+    use crate::Connectivity;
+
+    fn naive<T: Copy + PartialEq + Default>(data: &[T], width: usize, height: usize, connectivity: Connectivity) -> (Vec<u32>, u32) {
+        let mut labels = vec![0; data.len()];
+        let mut count = 0;
+        for start in 0..data.len() {
+            if data[start] == T::default() || labels[start] != 0 {
+                continue;
+            }
+            count += 1;
+            labels[start] = count;
+            let mut stack = vec![start];
+            while let Some(i) = stack.pop() {
+                for &offset in connectivity.neighbors() {
+                    if let Some(j) = Connectivity::neighbor(i, offset, width, height) {
+                        if labels[j] == 0 && data[j] == data[i] {
+                            labels[j] = count;
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+        }
+        (labels, count)
+    }
+
+    #[test]
+    fn label() {
+        let (width, height) = (57, 150);
+        let binary = PhysicalImage::from_fn(width, height, |x, y| (x * 7 + y * 13 + x * y) % 5 < 2 || x == 20);
+        let multi = PhysicalImage::from_fn(width, height, |x, y| ((x / 3 + y / 4 + x * y / 11) % 4) as u8);
+        for &connectivity in [Connectivity::Four, Connectivity::Eight].iter() {
+            let (labels, count) = label_components(&binary, connectivity);
+            assert_eq!(
+                (labels.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), count),
+                naive(&binary.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), width, height, connectivity),
+                "{:?}",
+                connectivity
+            );
+            let (labels, count) = label_components(&multi, connectivity);
+            assert_eq!(
+                (labels.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), count),
+                naive(&multi.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), width, height, connectivity),
+                "{:?}",
+                connectivity
+            );
+        }
+
+        let diagonal = PhysicalImage::from_fn(3, 3, |x, y| x == y);
+        assert_eq!(label_components(&diagonal, Connectivity::Four).1, 3);
+        assert_eq!(
+            label_components(&diagonal, Connectivity::Eight).0.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![1, 0, 0, 0, 1, 0, 0, 0, 1]
+        );
+        assert_eq!(label_components(&PhysicalImage::<bool>::new(0, 4), Connectivity::Four).1, 0);
+    }
+}
@@ -3,15 +3,30 @@
 
 //! A crate for image processing by processing for each pixels.
 
+use image::{Bgr, Bgra, Luma, LumaA, Primitive, Rgb, Rgba};
 use partial_const::MayBeConst;
 use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
 
-use crate::image_ref::{ImageRef, ImageRefMut, ImageRefOverhang, ImageRefOverhangMut};
+use crate::image_ref::{ImageRef, ImageRefMut, ImageRefOverhang, ImageRefOverhangMut, ImageRefStrided, ImageRefStridedMut};
 use crate::pixel_iter::{PixIter, SerializePixIter};
 
+pub mod border;
+pub mod convolution;
+pub mod histogram;
 pub mod image_ref;
+pub mod integral;
+pub mod label;
+pub mod morphology;
 pub mod physical_image;
 pub mod pixel_iter;
+pub mod rank;
+pub mod reconstruction;
+pub mod region;
+pub mod resize;
+pub mod sample;
+pub mod statistics;
+pub mod tile;
+pub mod warp;
 
 #[derive(Debug, Clone)]
 pub struct Rectangle {
@@ -27,6 +42,98 @@ impl Rectangle {
     }
 }
 
+/// Which neighbors of a pixel are connected to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Left, right, upper and lower neighbors.
+    Four,
+    /// Four neighbors and diagonal neighbors.
+    Eight,
+}
+
+impl Connectivity {
+    /// Get offsets (dx, dy) of neighbors which come before the pixel in row-major order.
+    pub(crate) fn preceding(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0)],
+        }
+    }
+
+    /// Get offsets (dx, dy) of all neighbors.
+    pub(crate) fn neighbors(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+
+    /// Get index of the neighbor at offset from pixel index in a width x height image, if it is inside of the image.
+    pub(crate) fn neighbor(index: usize, (dx, dy): (isize, isize), width: usize, height: usize) -> Option<usize> {
+        let (x, y) = ((index % width) as isize + dx, (index / width) as isize + dy);
+        if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+            None
+        } else {
+            Some(y as usize * width + x as usize)
+        }
+    }
+}
+
+/// A trait for pixel types whose channels can be read as f64.
+pub trait ChannelPixel {
+    /// Number of channels.
+    const CHANNELS: usize;
+    /// Get value of channel index.
+    fn channel(&self, index: usize) -> f64;
+}
+
+macro_rules! impl_channel_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl ChannelPixel for $t {
+                const CHANNELS: usize = 1;
+
+                fn channel(&self, _: usize) -> f64 {
+                    *self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_channel_primitive!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+macro_rules! impl_channel_pixel {
+    ($($pixel:ident, $channels:expr;)*) => {
+        $(
+            impl<S: Primitive + ChannelPixel> ChannelPixel for $pixel<S> {
+                const CHANNELS: usize = $channels;
+
+                fn channel(&self, index: usize) -> f64 {
+                    self.0[index].channel(0)
+                }
+            }
+        )*
+    };
+}
+
+impl_channel_pixel! {
+    Luma, 1;
+    LumaA, 2;
+    Rgb, 3;
+    Rgba, 4;
+    Bgr, 3;
+    Bgra, 4;
+}
+
+impl<T: ChannelPixel> ChannelPixel for &T {
+    const CHANNELS: usize = T::CHANNELS;
+
+    fn channel(&self, index: usize) -> f64 {
+        (**self).channel(index)
+    }
+}
+
 /// A trait for reading value of specified pixel.
 pub trait ReadPixel {
     /// A type of item in each pixel.
@@ -92,6 +199,14 @@ pub trait View: ReadPixel {
     /// Rectangle {x, y, w, h} should be valid.
     unsafe fn view_unchecked<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: usize, y: usize, w: RW, h: RH) -> ImageRef<Self::Item, RW, RH>;
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<Self::Item, RW, RH>;
+    /// Get area reference of image which visits every step_x-th column and every step_y-th row of rectangle {x, y, w, h}.
+    /// If view_is_valid(x, y, w, h) == false or a step is 0, this function returns None.
+    fn view_strided(&self, x: usize, y: usize, w: usize, h: usize, step_x: usize, step_y: usize) -> Option<ImageRefStrided<'_, Self::Item>> {
+        if step_x == 0 || step_y == 0 {
+            return None;
+        }
+        self.view(x, y, w, h).map(|view| ImageRefStrided::from(view).subsample(step_x, step_y))
+    }
 }
 
 /// A trait for getting mutable area reference of image.
@@ -110,6 +225,36 @@ pub trait ViewMut: View + WritePixel {
     /// Rectangle {x, y, w, h} should be valid.
     unsafe fn view_unchecked_mut<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&mut self, x: usize, y: usize, w: RW, h: RH) -> ImageRefMut<Self::Item, RW, RH>;
     fn view_overhang_mut<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&mut self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhangMut<Self::Item, RW, RH>;
+    /// Get mutable area reference of image which visits every step_x-th column and every step_y-th row of rectangle {x, y, w, h}.
+    /// If view_is_valid(x, y, w, h) == false or a step is 0, this function returns None.
+    fn view_strided_mut(&mut self, x: usize, y: usize, w: usize, h: usize, step_x: usize, step_y: usize) -> Option<ImageRefStridedMut<'_, Self::Item>> {
+        if step_x == 0 || step_y == 0 {
+            return None;
+        }
+        self.view_mut(x, y, w, h).map(|view| ImageRefStridedMut::from(view).subsample(step_x, step_y))
+    }
+    /// Split this image into two disjoint mutable area references, columns [0, x) and [x, width).
+    /// If x > width() or any pixel of this image is not valid, this function returns None.
+    fn split_at_x(&mut self, x: usize) -> Option<(ImageRefMut<Self::Item>, ImageRefMut<Self::Item>)> {
+        let (width, height) = (self.width(), self.height());
+        if x <= width && self.view_is_valid(0, 0, width, height) {
+            let this: *mut Self = self;
+            Some(unsafe { ((*this).view_unchecked_mut(0, 0, x, height), (*this).view_unchecked_mut(x, 0, width - x, height)) })
+        } else {
+            None
+        }
+    }
+    /// Split this image into two disjoint mutable area references, rows [0, y) and [y, height).
+    /// If y > height() or any pixel of this image is not valid, this function returns None.
+    fn split_at_y(&mut self, y: usize) -> Option<(ImageRefMut<Self::Item>, ImageRefMut<Self::Item>)> {
+        let (width, height) = (self.width(), self.height());
+        if y <= height && self.view_is_valid(0, 0, width, height) {
+            let this: *mut Self = self;
+            Some(unsafe { ((*this).view_unchecked_mut(0, 0, width, y), (*this).view_unchecked_mut(0, y, width, height - y)) })
+        } else {
+            None
+        }
+    }
 }
 
 pub trait IntoPixelIterator {
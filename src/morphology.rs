@@ -0,0 +1,471 @@
+//! Erosion, dilation and operations built from them.
+
+use image::{Luma, Primitive};
+
+use crate::convolution::par_rows;
+use crate::physical_image::PhysicalImage;
+use crate::ReadPixel;
+
+/// A pixel type which can be processed by morphology.
+pub trait MorphologyPixel: Copy + Send + Sync {
+    /// The largest value, which is the identity of min.
+    const MAX: Self;
+    /// The smallest value, which is the identity of max.
+    const MIN: Self;
+    /// Get the smaller value.
+    fn min(self, other: Self) -> Self;
+    /// Get the larger value.
+    fn max(self, other: Self) -> Self;
+    /// Get self - other, saturated at the smallest value.
+    fn difference(self, other: Self) -> Self;
+}
+
+impl MorphologyPixel for bool {
+    const MAX: bool = true;
+    const MIN: bool = false;
+
+    fn min(self, other: bool) -> bool {
+        self && other
+    }
+
+    fn max(self, other: bool) -> bool {
+        self || other
+    }
+
+    fn difference(self, other: bool) -> bool {
+        self && !other
+    }
+}
+
+macro_rules! impl_morphology_integer {
+    ($($t:ty),*) => {
+        $(
+            impl MorphologyPixel for $t {
+                const MAX: $t = <$t>::MAX;
+                const MIN: $t = <$t>::MIN;
+
+                fn min(self, other: $t) -> $t {
+                    Ord::min(self, other)
+                }
+
+                fn max(self, other: $t) -> $t {
+                    Ord::max(self, other)
+                }
+
+                fn difference(self, other: $t) -> $t {
+                    self.saturating_sub(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_morphology_integer!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+macro_rules! impl_morphology_float {
+    ($($t:ty),*) => {
+        $(
+            impl MorphologyPixel for $t {
+                const MAX: $t = <$t>::INFINITY;
+                const MIN: $t = <$t>::NEG_INFINITY;
+
+                fn min(self, other: $t) -> $t {
+                    <$t>::min(self, other)
+                }
+
+                fn max(self, other: $t) -> $t {
+                    <$t>::max(self, other)
+                }
+
+                fn difference(self, other: $t) -> $t {
+                    self - other
+                }
+            }
+        )*
+    };
+}
+
+impl_morphology_float!(f32, f64);
+
+impl<S: MorphologyPixel + Primitive> MorphologyPixel for Luma<S> {
+    const MAX: Self = Luma([S::MAX]);
+    const MIN: Self = Luma([S::MIN]);
+
+    fn min(self, other: Self) -> Self {
+        Luma([self.0[0].min(other.0[0])])
+    }
+
+    fn max(self, other: Self) -> Self {
+        Luma([self.0[0].max(other.0[0])])
+    }
+
+    fn difference(self, other: Self) -> Self {
+        Luma([self.0[0].difference(other.0[0])])
+    }
+}
+
+/// A shape of neighborhood for morphology. The anchor of every element is (width / 2, height / 2) of its bounding box.
+/// An element with a side of 0 covers no pixels, so that erosion yields the largest value and dilation the smallest.
+#[derive(Debug)]
+pub enum StructuringElement {
+    /// A filled rectangle, processed by the van Herk/Gil-Werman algorithm in time independent of its size.
+    Rectangle {
+        /// Width of the rectangle.
+        width: usize,
+        /// Height of the rectangle.
+        height: usize,
+    },
+    /// A horizontal line and a vertical line crossing at the anchor.
+    Cross {
+        /// Length of the horizontal line.
+        width: usize,
+        /// Length of the vertical line.
+        height: usize,
+    },
+    /// A disk of pixels whose distance from the center is at most radius.
+    Disk {
+        /// Radius of the disk.
+        radius: usize,
+    },
+    /// Pixels which are true in the mask.
+    Custom(PhysicalImage<bool>),
+}
+
+impl StructuringElement {
+    /// Get offsets of pixels from the anchor, reflected if reflect is true.
+    fn offsets(&self, reflect: bool) -> Vec<(isize, isize)> {
+        let offsets = match self {
+            StructuringElement::Rectangle { width, height } => grid(*width, *height, |_, _| true),
+            StructuringElement::Cross { width, height } => grid(*width, *height, |x, y| x == width / 2 || y == height / 2),
+            StructuringElement::Disk { radius } => grid(radius * 2 + 1, radius * 2 + 1, |x, y| {
+                let (dx, dy) = ((x as isize - *radius as isize).unsigned_abs(), (y as isize - *radius as isize).unsigned_abs());
+                dx * dx + dy * dy <= radius * radius
+            }),
+            StructuringElement::Custom(mask) => grid(mask.width(), mask.height(), |x, y| *mask.get(x, y).unwrap()),
+        };
+        if reflect {
+            offsets.into_iter().map(|(x, y)| (-x, -y)).collect()
+        } else {
+            offsets
+        }
+    }
+}
+
+fn grid(width: usize, height: usize, f: impl Fn(usize, usize) -> bool) -> Vec<(isize, isize)> {
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| f(x, y))
+        .map(|(x, y)| (x as isize - (width / 2) as isize, y as isize - (height / 2) as isize))
+        .collect()
+}
+
+/// Write op of window line[i - anchor..i - anchor + length] into out[i] by the van Herk/Gil-Werman algorithm.
+/// Pixels outside of line are identity.
+fn van_herk<T: Copy>(line: &[T], length: usize, anchor: usize, identity: T, op: fn(T, T) -> T, out: &mut [T]) {
+    let padded_length = line.len() + length - 1;
+    let padded = |j: usize| if j >= anchor && j - anchor < line.len() { line[j - anchor] } else { identity };
+    let mut prefix = Vec::with_capacity(padded_length);
+    for j in 0..padded_length {
+        let value = if j % length == 0 { padded(j) } else { op(prefix[j - 1], padded(j)) };
+        prefix.push(value);
+    }
+    let mut suffix = vec![identity; padded_length];
+    for j in (0..padded_length).rev() {
+        suffix[j] = if j % length == length - 1 || j == padded_length - 1 {
+            padded(j)
+        } else {
+            op(suffix[j + 1], padded(j))
+        };
+    }
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = op(suffix[i], prefix[i + length - 1]);
+    }
+}
+
+/// Write op of windows of length along each row of source into the rows of a new image, keeping the anchor in each window.
+fn rows<S>(source: &S, length: usize, anchor: usize, identity: S::Item, op: fn(S::Item, S::Item) -> S::Item) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    let width = source.width();
+    par_rows(width, source.height(), |y, row| {
+        let line = (0..width).map(|x| source.get(x, y).copied().unwrap_or(identity)).collect::<Vec<_>>();
+        van_herk(&line, length, anchor, identity, op, row);
+    })
+}
+
+/// Filter rows of source and then rows of the transposed result, so that both passes run along contiguous rows.
+fn rectangle<S>(source: &S, width: usize, height: usize, identity: S::Item, op: fn(S::Item, S::Item) -> S::Item, reflect: bool) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    if width == 0 || height == 0 {
+        return PhysicalImage::with_default(source.width(), source.height(), identity);
+    }
+    let anchor = |length: usize| if reflect { length - 1 - length / 2 } else { length / 2 };
+    let horizontal = rows(source, width, anchor(width), identity, op);
+    if height == 1 {
+        return horizontal;
+    }
+    let vertical = rows(&horizontal.transpose(), height, anchor(height), identity, op);
+    vertical.transpose().pix_iter().map(|&value| value).collect_image()
+}
+
+fn masked<S>(source: &S, offsets: &[(isize, isize)], identity: S::Item, op: fn(S::Item, S::Item) -> S::Item) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    par_rows(source.width(), source.height(), |y, row| {
+        for (x, value) in row.iter_mut().enumerate() {
+            *value = offsets.iter().fold(identity, |accumulator, &(dx, dy)| {
+                let (source_x, source_y) = (x as isize + dx, y as isize + dy);
+                if source_x < 0 || source_y < 0 {
+                    return accumulator;
+                }
+                source.get(source_x as usize, source_y as usize).map_or(accumulator, |&value| op(accumulator, value))
+            });
+        }
+    })
+}
+
+fn filter<S>(source: &S, element: &StructuringElement, identity: S::Item, op: fn(S::Item, S::Item) -> S::Item, reflect: bool) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    match element {
+        StructuringElement::Rectangle { width, height } => rectangle(source, *width, *height, identity, op, reflect),
+        StructuringElement::Cross { width, height } if *width == 0 || *height == 0 => rectangle(source, 0, 0, identity, op, reflect),
+        StructuringElement::Cross { width, height } => {
+            let horizontal = rectangle(source, *width, 1, identity, op, reflect);
+            let vertical = rectangle(source, 1, *height, identity, op, reflect);
+            horizontal.pix_iter().zip(vertical.pix_iter()).map(|(&a, &b)| op(a, b)).collect_image()
+        }
+        _ => masked(source, &element.offsets(reflect), identity, op),
+    }
+}
+
+/// Erode source image by element: the output pixel is the minimum of source pixels covered by element at the anchor.
+/// Pixels outside of valid area of source are ignored, i.e. treated as the identity of min.
+pub fn erode<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    filter(source, element, S::Item::MAX, S::Item::min, false)
+}
+
+/// Dilate source image by element: the output pixel is the maximum of source pixels covered by element reflected at the anchor.
+/// Pixels outside of valid area of source are ignored, i.e. treated as the identity of max.
+pub fn dilate<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    filter(source, element, S::Item::MIN, S::Item::max, true)
+}
+
+/// Open source image by element, i.e. dilate the eroded image. This removes bright parts smaller than element.
+pub fn open<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    dilate(&erode(source, element), element)
+}
+
+/// Close source image by element, i.e. erode the dilated image. This removes dark parts smaller than element.
+pub fn close<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    erode(&dilate(source, element), element)
+}
+
+/// Get the difference between dilated and eroded images, which is large around edges.
+pub fn gradient<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    let (dilated, eroded) = (dilate(source, element), erode(source, element));
+    dilated.pix_iter().zip(eroded.pix_iter()).map(|(&a, &b)| a.difference(b)).collect_image()
+}
+
+/// Get the difference between source and opened images, which keeps bright parts smaller than element.
+/// Pixels outside of valid area of source are the smallest value.
+pub fn top_hat<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    let opened = open(source, element);
+    opened
+        .pix_iter()
+        .with_coords()
+        .map(|((x, y), &b)| source.get(x, y).map_or(S::Item::MIN, |&a| a.difference(b)))
+        .collect_image()
+}
+
+/// Get the difference between closed and source images, which keeps dark parts smaller than element.
+/// Pixels outside of valid area of source are the smallest value.
+pub fn black_hat<S>(source: &S, element: &StructuringElement) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: MorphologyPixel,
+{
+    let closed = close(source, element);
+    closed
+        .pix_iter()
+        .with_coords()
+        .map(|((x, y), &a)| source.get(x, y).map_or(S::Item::MIN, |&b| a.difference(b)))
+        .collect_image()
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Luma;
+
+    use crate::morphology::{black_hat, close, dilate, erode, gradient, open, top_hat, StructuringElement};
+    use crate::physical_image::PhysicalImage;
+    use crate::{ReadPixel, View};
+
+    fn naive(image: &PhysicalImage<u8>, mask: &PhysicalImage<bool>, erosion: bool) -> PhysicalImage<u8> {
+        let (anchor_x, anchor_y) = ((mask.width() / 2) as isize, (mask.height() / 2) as isize);
+        PhysicalImage::from_fn(image.width(), image.height(), |x, y| {
+            let mut result = if erosion { u8::MAX } else { u8::MIN };
+            for j in 0..mask.height() as isize {
+                for i in 0..mask.width() as isize {
+                    if !mask.get(i as usize, j as usize).unwrap() {
+                        continue;
+                    }
+                    let (dx, dy) = if erosion { (i - anchor_x, j - anchor_y) } else { (anchor_x - i, anchor_y - j) };
+                    let (source_x, source_y) = (x as isize + dx, y as isize + dy);
+                    if source_x < 0 || source_y < 0 {
+                        continue;
+                    }
+                    if let Some(&value) = image.get(source_x as usize, source_y as usize) {
+                        result = if erosion { result.min(value) } else { result.max(value) };
+                    }
+                }
+            }
+            result
+        })
+    }
+
+    #[test]
+    fn erode_dilate() {
+        let image = PhysicalImage::from_fn(13, 9, |x, y| ((x * 37 + y * 101 + x * y * 7) % 251) as u8);
+        let elements = [
+            (StructuringElement::Rectangle { width: 1, height: 1 }, PhysicalImage::with_default(1, 1, true)),
+            (StructuringElement::Rectangle { width: 3, height: 5 }, PhysicalImage::with_default(3, 5, true)),
+            (StructuringElement::Rectangle { width: 4, height: 2 }, PhysicalImage::with_default(4, 2, true)),
+            (StructuringElement::Rectangle { width: 17, height: 1 }, PhysicalImage::with_default(17, 1, true)),
+            (StructuringElement::Cross { width: 5, height: 4 }, PhysicalImage::from_fn(5, 4, |x, y| x == 2 || y == 2)),
+            (
+                StructuringElement::Disk { radius: 2 },
+                PhysicalImage::with_data(5, 5, [0, 0, 1, 0, 0, 0, 1, 1, 1, 0, 1, 1, 1, 1, 1, 0, 1, 1, 1, 0, 0, 0, 1, 0, 0].iter().map(|&v| v == 1).collect()),
+            ),
+        ];
+        for (element, mask) in elements.iter() {
+            assert_eq!(
+                erode(&image, element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                naive(&image, mask, true).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                "{:?}",
+                element
+            );
+            assert_eq!(
+                dilate(&image, element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                naive(&image, mask, false).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                "{:?}",
+                element
+            );
+        }
+        let mask = PhysicalImage::from_fn(4, 3, |x, y| (x + y) % 3 == 0);
+        let naive_result = naive(&image, &mask, true);
+        assert_eq!(
+            erode(&image, &StructuringElement::Custom(mask)).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            naive_result.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            erode(&image, &StructuringElement::Rectangle { width: 0, height: 3 })
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![u8::MAX; 13 * 9]
+        );
+        assert_eq!(
+            erode(&image, &StructuringElement::Cross { width: 0, height: 3 })
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![u8::MAX; 13 * 9]
+        );
+        assert_eq!(
+            dilate(&image, &StructuringElement::Cross { width: 3, height: 0 })
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![u8::MIN; 13 * 9]
+        );
+
+        let overhang = image.view_overhang(-2, 1, 16, 6);
+        let eroded = erode(&overhang, &StructuringElement::Rectangle { width: 3, height: 3 });
+        let valid = erode(&image.view(0, 1, 13, 6).unwrap(), &StructuringElement::Rectangle { width: 3, height: 3 });
+        assert_eq!(
+            eroded.view(2, 0, 13, 6).unwrap().pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            valid.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(eroded.get(0, 0), Some(&u8::MAX));
+        let luma = PhysicalImage::from_fn(3, 1, |x, _| Luma([x as u16 * 10]));
+        assert_eq!(
+            dilate(&luma, &StructuringElement::Rectangle { width: 3, height: 1 })
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![Luma([10]), Luma([20]), Luma([20])]
+        );
+    }
+
+    #[test]
+    fn open_close() {
+        let binary = PhysicalImage::from_fn(10, 8, |x, y| ((2..8).contains(&x) && (2..6).contains(&y) && (x, y) != (4, 3)) || (x, y) == (0, 7));
+        let element = StructuringElement::Rectangle { width: 3, height: 3 };
+        let opened = open(&binary, &element);
+        assert_eq!(opened.get(0, 7), Some(&false));
+        assert_eq!(opened.get(6, 4), Some(&true));
+        let closed = close(&binary, &element);
+        assert_eq!(closed.get(4, 3), Some(&true));
+        assert_eq!(
+            close(&closed, &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            closed.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            open(&opened, &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            opened.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        let boundary = gradient(&binary, &StructuringElement::Cross { width: 3, height: 3 });
+        assert_eq!((boundary.get(2, 2), boundary.get(1, 2), boundary.get(6, 4)), (Some(&true), Some(&true), Some(&false)));
+
+        let spot = |value: u8| PhysicalImage::from_fn(7, 5, move |x, y| if (x, y) == (3, 2) { value } else { 40 });
+        let only = |value: u8| PhysicalImage::from_fn(7, 5, move |x, y| if (x, y) == (3, 2) { value } else { 0 });
+        assert_eq!(
+            top_hat(&spot(90), &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            only(50).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(black_hat(&spot(90), &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![0; 35]);
+        assert_eq!(
+            black_hat(&spot(0), &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            only(40).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(top_hat(&spot(0), &element).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![0; 35]);
+    }
+}
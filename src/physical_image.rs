@@ -3,17 +3,19 @@ use std::path::Path;
 
 use image::buffer::ConvertBuffer;
 use image::{Bgr, Bgra, DynamicImage, EncodableLayout, ImageBuffer, ImageResult, Luma, LumaA, Pixel, Rgb, Rgba};
-use partial_const::MayBeConst;
+use partial_const::{ConstUsize, MayBeConst};
 use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 
-use crate::image_ref::{ImageRef, ImageRefMut, ImageRefOverhang, ImageRefOverhangMut};
+use crate::image_ref::{ImageRef, ImageRefMut, ImageRefOverhang, ImageRefOverhangMut, ImageRefStrided, ImageRefStridedMut};
 use crate::pixel_iter::{PixIter, SerializePixIter};
 use crate::{IntoPixelIterator, IntoSerializedPixelIterator, ReadPixel, Rectangle, View, ViewMut, WritePixel};
 
 #[derive(Debug)]
 pub struct PhysicalImage<T, W: MayBeConst<usize> = usize, H: MayBeConst<usize> = usize> {
-    width: W,
-    height: H,
+    pub(crate) width: W,
+    pub(crate) height: H,
+    pub(crate) stride: usize,
+    pub(crate) offset: usize,
     pub(crate) data: Vec<T>,
 }
 
@@ -48,15 +50,120 @@ impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
         }
     }
 
+    /// Create image whose pixel (x, y) is f(x, y), evaluating f in parallel.
+    pub fn from_fn<F: Fn(usize, usize) -> T + Sync + Send>(width: W, height: H, f: F) -> Self
+    where
+        T: Send,
+    {
+        let w = width.value();
+        let mut data = Vec::with_capacity(w * height.value());
+        (0..w * height.value()).into_par_iter().map(|i| f(i % w, i / w)).collect_into_vec(&mut data);
+        Self::with_data(width, height, data)
+    }
+
     pub unsafe fn new_uninit(width: W, height: H) -> Self {
         let mut data = Vec::<T>::with_capacity(width.value() * height.value());
         data.set_len(width.value() * height.value());
-        Self { width, height, data }
+        Self::with_data(width, height, data)
     }
 
     pub(crate) fn with_data(width: W, height: H, data: Vec<T>) -> Self {
         debug_assert_eq!(data.len(), width.value() * height.value());
-        Self { width, height, data }
+        Self {
+            width,
+            height,
+            stride: width.value(),
+            offset: 0,
+            data,
+        }
+    }
+
+    /// Create image whose rows start at addresses aligned to alignment bytes, filled with default value.
+    /// Each row is padded so that stride() * size_of::<T>() is a multiple of alignment.
+    /// # Panics
+    /// Panics if alignment is not a power of two, or if it cannot be reached by steps of size_of::<T>() from addresses aligned for T,
+    /// for example alignment 4 for [u8; 2].
+    pub fn new_aligned(width: W, height: H, alignment: usize) -> Self
+    where
+        T: Default,
+    {
+        Self::aligned_with(width, height, alignment, T::default)
+    }
+
+    /// Create image whose rows start at addresses aligned to alignment bytes, filled with default.
+    /// Each row is padded so that stride() * size_of::<T>() is a multiple of alignment.
+    /// # Panics
+    /// Panics if alignment is not a power of two, or if it cannot be reached by steps of size_of::<T>() from addresses aligned for T,
+    /// for example alignment 4 for [u8; 2].
+    pub fn with_default_aligned(width: W, height: H, default: T, alignment: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self::aligned_with(width, height, alignment, || default.clone())
+    }
+
+    fn aligned_with(width: W, height: H, alignment: usize, f: impl FnMut() -> T) -> Self {
+        assert!(alignment.is_power_of_two(), "alignment {} is not a power of two", alignment);
+        let size = std::mem::size_of::<T>();
+        // Addresses of elements advance by size, so they reach multiples of granule = gcd(size, alignment) only.
+        let granule = if size == 0 { 1 } else { alignment.min(1 << size.trailing_zeros()) };
+        assert!(
+            granule <= std::mem::align_of::<T>(),
+            "alignment {} cannot be reached by elements of {} bytes aligned to {}",
+            alignment,
+            size,
+            std::mem::align_of::<T>()
+        );
+        if size == 0 || width.value() == 0 || height.value() == 0 {
+            let mut data = Vec::new();
+            data.resize_with(width.value() * height.value(), f);
+            return Self::with_data(width, height, data);
+        }
+        let step = alignment / granule;
+        let stride = (width.value() / step + (width.value() % step != 0) as usize) * step;
+        let len = stride * height.value();
+        let mut data = Vec::with_capacity(step - 1 + len);
+        let address = data.as_ptr() as usize;
+        let offset = (0..step)
+            .find(|i| (address + i * size) % alignment == 0)
+            .expect("an address aligned for T reaches alignment within step elements");
+        data.resize_with(offset + len, f);
+        Self { width, height, stride, offset, data }
+    }
+
+    /// Get the number of elements between starts of adjacent rows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Get a pointer to pixel (0, 0). Pixel (x, y) is located at offset y * stride() + x.
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr().wrapping_add(self.offset)
+    }
+
+    /// Get a mutable pointer to pixel (0, 0). Pixel (x, y) is located at offset y * stride() + x.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr().wrapping_add(self.offset)
+    }
+
+    /// Take width, height and pixels in row-major order without padding.
+    pub(crate) fn into_packed(self) -> (W, H, Vec<T>) {
+        let PhysicalImage {
+            width,
+            height,
+            stride,
+            offset,
+            mut data,
+        } = self;
+        if stride != width.value() || offset != 0 {
+            let mut index = 0;
+            data.retain(|_| {
+                let keep = index >= offset && (index - offset) % stride < width.value();
+                index += 1;
+                keep
+            });
+        }
+        (width, height, data)
     }
 }
 
@@ -99,7 +206,7 @@ where
             .chunks(P::CHANNEL_COUNT as usize)
             .map(|v| *P::from_slice(&v))
             .collect_into_vec(&mut data);
-        Self { width, height, data }
+        Self::with_data(width, height, data)
     }
 }
 
@@ -110,7 +217,7 @@ where
     P::Subpixel: Send,
 {
     fn from(image: PhysicalImage<P, W, H>) -> Self {
-        let PhysicalImage { width, height, data } = image;
+        let (width, height, data) = image.into_packed();
         let width = width.value() as u32;
         let height = height.value() as u32;
         unsafe {
@@ -183,14 +290,14 @@ impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> ReadPixel for PhysicalImage<
 
     unsafe fn get_unchecked(&self, x: usize, y: usize) -> &Self::Item {
         debug_assert!(self.is_valid(x, y), "Location ({}, {}) is not valid in PhysicalImage::get_unchecked", x, y);
-        self.data.get_unchecked(self.width.value() * y + x)
+        self.data.get_unchecked(self.offset + self.stride * y + x)
     }
 }
 
 impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> WritePixel for PhysicalImage<T, W, H> {
     unsafe fn get_unchecked_mut(&mut self, x: usize, y: usize) -> &mut Self::Item {
         debug_assert!(self.is_valid(x, y), "Location ({}, {}) is not valid in PhysicalImage::get_unchecked_mut", x, y);
-        self.data.get_unchecked_mut(self.width.value() * y + x)
+        self.data.get_unchecked_mut(self.offset + self.stride * y + x)
     }
 }
 
@@ -208,21 +315,11 @@ impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> View for PhysicalImage<T, W,
             w,
             h
         );
-        ImageRef::new(self.width.value(), self.data.as_ptr(), x, y, w, h)
+        ImageRef::new(self.stride, self.as_ptr(), x, y, w, h)
     }
 
     fn view_overhang<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhang<T, RW, RH> {
-        let valid_x = x.clamp(0, self.width.value() as isize) as usize;
-        let valid_y = y.clamp(0, self.height.value() as isize) as usize;
-        let valid_width = (x + w.value() as isize).clamp(0, self.width.value() as isize) as usize - valid_x;
-        let valid_height = (y + h.value() as isize).clamp(0, self.height.value() as isize) as usize - valid_y;
-        ImageRefOverhang::new(
-            unsafe { self.view_unchecked(valid_x, valid_y, valid_width, valid_height) },
-            (-x).max(0) as usize,
-            (-y).max(0) as usize,
-            w,
-            h,
-        )
+        ImageRefOverhang::new(unsafe { self.view_unchecked(0, 0, self.width.value(), self.height.value()) }, x, y, w, h)
     }
 }
 
@@ -236,21 +333,11 @@ impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> ViewMut for PhysicalImage<T,
             w,
             h
         );
-        ImageRefMut::new(self.width.value(), self.data.as_mut_ptr(), x, y, w, h)
+        ImageRefMut::new(self.stride, self.as_mut_ptr(), x, y, w, h)
     }
 
     fn view_overhang_mut<RW: MayBeConst<usize>, RH: MayBeConst<usize>>(&mut self, x: isize, y: isize, w: RW, h: RH) -> ImageRefOverhangMut<T, RW, RH> {
-        let valid_x = x.clamp(0, self.width.value() as isize) as usize;
-        let valid_y = y.clamp(0, self.height.value() as isize) as usize;
-        let valid_width = (x + w.value() as isize).clamp(0, self.width.value() as isize) as usize - valid_x;
-        let valid_height = (y + h.value() as isize).clamp(0, self.height.value() as isize) as usize - valid_y;
-        ImageRefOverhangMut::new(
-            unsafe { self.view_unchecked_mut(valid_x, valid_y, valid_width, valid_height) },
-            (-x).max(0) as usize,
-            (-y).max(0) as usize,
-            w,
-            h,
-        )
+        ImageRefOverhangMut::new(unsafe { self.view_unchecked_mut(0, 0, self.width.value(), self.height.value()) }, x, y, w, h)
     }
 }
 
@@ -261,7 +348,7 @@ impl<T: Send, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for
     type Iter = rayon::vec::IntoIter<T>;
 
     fn into_pix_iter(self) -> PixIter<Self::Iter, Self::Width, Self::Height> {
-        let PhysicalImage { width, height, data } = self;
+        let (width, height, data) = self.into_packed();
         PixIter::new(data.into_par_iter(), width, height)
     }
 }
@@ -272,10 +359,140 @@ impl<T: Send, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H>
     }
 }
 
+impl<T: Send + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Iterate disjoint mutable tiles of size (tile_width, tile_height) covering this image in parallel.
+    /// Tiles on the right and bottom edges may be smaller. The iterator has the size of the tile grid.
+    /// # Panics
+    /// Panics if tile_width or tile_height is 0.
+    pub fn par_tiles_mut(&mut self, tile_width: usize, tile_height: usize) -> PixIter<impl IndexedParallelIterator<Item = ImageRefMut<'_, T>>, usize, usize> {
+        self.view_mut(0, 0, self.width, self.height).unwrap().into_tiles_mut(tile_width, tile_height)
+    }
+}
+
 impl<T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
     pub fn pix_iter(&self) -> PixIter<impl ParallelIterator<Item = &T> + IndexedParallelIterator, W, H> {
         self.view(0, 0, self.width, self.height).unwrap().pix_iter()
     }
+
+    /// Iterate windows of size (kernel_width, kernel_height) centered on each pixel.
+    /// Pixels of a window outside of this image are not valid in the window.
+    pub fn pix_iter_window<KW: MayBeConst<usize>, KH: MayBeConst<usize>>(
+        &self,
+        kernel_width: KW,
+        kernel_height: KH,
+    ) -> PixIter<impl IndexedParallelIterator<Item = ImageRefOverhang<'_, T, KW, KH>>, W, H> {
+        self.view(0, 0, self.width, self.height).unwrap().pix_iter_window(kernel_width, kernel_height)
+    }
+}
+
+impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Iterate rows of this image as (y, row).
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = (usize, &[T])> + DoubleEndedIterator {
+        self.view(0, 0, self.width, self.height).unwrap().rows()
+    }
+
+    /// Iterate mutable rows of this image as (y, row).
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = (usize, &mut [T])> + DoubleEndedIterator {
+        self.view_mut(0, 0, self.width, self.height).unwrap().into_rows_mut()
+    }
+
+    /// Iterate columns of this image as (x, column).
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = (usize, ImageRef<T, ConstUsize<1>, usize>)> + DoubleEndedIterator {
+        self.view(0, 0, self.width, self.height).unwrap().columns()
+    }
+
+    /// Iterate mutable columns of this image as (x, column).
+    pub fn columns_mut(&mut self) -> impl ExactSizeIterator<Item = (usize, ImageRefMut<T, ConstUsize<1>, usize>)> + DoubleEndedIterator {
+        self.view_mut(0, 0, self.width, self.height).unwrap().into_columns_mut()
+    }
+}
+
+impl<T: Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Iterate rows of this image as (y, row) in parallel.
+    pub fn par_rows(&self) -> impl IndexedParallelIterator<Item = (usize, &[T])> {
+        self.view(0, 0, self.width, self.height).unwrap().par_rows()
+    }
+
+    /// Iterate columns of this image as (x, column) in parallel.
+    pub fn par_columns(&self) -> impl IndexedParallelIterator<Item = (usize, ImageRef<T, ConstUsize<1>, usize>)> {
+        self.view(0, 0, self.width, self.height).unwrap().par_columns()
+    }
+}
+
+impl<T: Send, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Iterate mutable rows of this image as (y, row) in parallel.
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, &mut [T])> {
+        self.view_mut(0, 0, self.width, self.height).unwrap().into_rows_mut()
+    }
+}
+
+impl<T: Send + Sync, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Iterate mutable columns of this image as (x, column) in parallel.
+    pub fn par_columns_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, ImageRefMut<T, ConstUsize<1>, usize>)> {
+        self.view_mut(0, 0, self.width, self.height).unwrap().into_columns_mut()
+    }
+}
+
+impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> PhysicalImage<T, W, H> {
+    /// Get a view of this image mirrored left and right.
+    pub fn flip_x(&self) -> ImageRefStrided<'_, T, W, H> {
+        self.view(0, 0, self.width, self.height).unwrap().flip_x()
+    }
+
+    /// Get a view of this image mirrored upside down.
+    pub fn flip_y(&self) -> ImageRefStrided<'_, T, W, H> {
+        self.view(0, 0, self.width, self.height).unwrap().flip_y()
+    }
+
+    /// Get a view of this image with x and y axes swapped.
+    pub fn transpose(&self) -> ImageRefStrided<'_, T, H, W> {
+        self.view(0, 0, self.width, self.height).unwrap().transpose()
+    }
+
+    /// Get a view of this image rotated by 90 degrees clockwise.
+    pub fn rotate90(&self) -> ImageRefStrided<'_, T, H, W> {
+        self.view(0, 0, self.width, self.height).unwrap().rotate90()
+    }
+
+    /// Get a view of this image rotated by 180 degrees.
+    pub fn rotate180(&self) -> ImageRefStrided<'_, T, W, H> {
+        self.view(0, 0, self.width, self.height).unwrap().rotate180()
+    }
+
+    /// Get a view of this image rotated by 270 degrees clockwise.
+    pub fn rotate270(&self) -> ImageRefStrided<'_, T, H, W> {
+        self.view(0, 0, self.width, self.height).unwrap().rotate270()
+    }
+
+    /// Get a mutable view of this image mirrored left and right.
+    pub fn flip_x_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).flip_x()
+    }
+
+    /// Get a mutable view of this image mirrored upside down.
+    pub fn flip_y_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).flip_y()
+    }
+
+    /// Get a mutable view of this image with x and y axes swapped.
+    pub fn transpose_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).transpose()
+    }
+
+    /// Get a mutable view of this image rotated by 90 degrees clockwise.
+    pub fn rotate90_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).rotate90()
+    }
+
+    /// Get a mutable view of this image rotated by 180 degrees.
+    pub fn rotate180_mut(&mut self) -> ImageRefStridedMut<'_, T, W, H> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).rotate180()
+    }
+
+    /// Get a mutable view of this image rotated by 270 degrees clockwise.
+    pub fn rotate270_mut(&mut self) -> ImageRefStridedMut<'_, T, H, W> {
+        ImageRefStridedMut::from(self.view_mut(0, 0, self.width, self.height).unwrap()).rotate270()
+    }
 }
 
 impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelIterator for PhysicalImage<T, W, H> {
@@ -285,7 +502,7 @@ impl<T, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelIterator
     type Iter = std::vec::IntoIter<T>;
 
     fn into_pix_iter_serialized(self) -> SerializePixIter<Self::Iter, Self::Width, Self::Height> {
-        let PhysicalImage { width, height, data } = self;
+        let (width, height, data) = self.into_packed();
         SerializePixIter::new(data.into_iter(), width, height)
     }
 }
@@ -305,6 +522,7 @@ mod tests {
     use std::sync::atomic::AtomicUsize;
 
     use image::{ImageBuffer, Rgb};
+    use partial_const::ConstUsize;
     use rayon::prelude::IndexedParallelIterator;
     use rayon::prelude::IntoParallelRefMutIterator;
     use rayon::prelude::ParallelIterator;
@@ -452,6 +670,336 @@ mod tests {
         assert_eq!(image.pix_iter_serialized_mut().into_inner().collect::<Vec<_>>(), data.iter_mut().collect::<Vec<_>>());
     }
 
+    #[test]
+    fn iter_window() {
+        const WIDTH: usize = 20;
+        const HEIGHT: usize = 10;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = y * WIDTH + x;
+            }
+        }
+        let sum = image
+            .pix_iter_window(ConstUsize::<3>::new(), ConstUsize::<3>::new())
+            .into_inner()
+            .map(|window| (0..3).flat_map(|y| (0..3).map(move |x| (x, y))).filter_map(|(x, y)| window.get(x, y)).sum::<usize>())
+            .collect::<Vec<_>>();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let mut expect = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let (x, y) = (x as isize + dx, y as isize + dy);
+                        if (0..WIDTH as isize).contains(&x) && (0..HEIGHT as isize).contains(&y) {
+                            expect += y as usize * WIDTH + x as usize;
+                        }
+                    }
+                }
+                assert_eq!(sum[y * WIDTH + x], expect);
+            }
+        }
+        let windows = image.pix_iter_window(4usize, 2usize).collect_image();
+        assert_eq!(windows.width(), WIDTH);
+        assert_eq!(windows.height(), HEIGHT);
+        let window = windows.get(5, 5).unwrap();
+        assert_eq!(window.width(), 4);
+        assert_eq!(window.height(), 2);
+        assert_eq!(window.get(0, 0), Some(&(4 * WIDTH + 3)));
+        assert_eq!(window.get(3, 1), Some(&(5 * WIDTH + 6)));
+        let window = windows.get(0, 0).unwrap();
+        assert_eq!(window.get(1, 0), None);
+        assert_eq!(window.get(2, 1), Some(&0));
+    }
+
+    #[test]
+    fn tiles() {
+        const WIDTH: usize = 23;
+        const HEIGHT: usize = 17;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        let sizes = image
+            .par_tiles_mut(5, 4)
+            .into_inner()
+            .enumerate()
+            .map(|(i, mut tile)| {
+                for value in tile.pix_iter_serialized_mut().into_inner() {
+                    *value += i + 1;
+                }
+                (tile.width(), tile.height())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(sizes.len(), 5 * 5);
+        for (i, &size) in sizes.iter().enumerate() {
+            assert_eq!(size, (if i % 5 == 4 { 3 } else { 5 }, if i / 5 == 4 { 1 } else { 4 }));
+        }
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(image.get(x, y), Some(&(y / 4 * 5 + x / 5 + 1)));
+            }
+        }
+        let sums = image
+            .par_tiles_mut(10, 10)
+            .into_inner()
+            .map(|tile| Iterator::sum::<usize>(tile.pix_iter_serialized().into_inner()))
+            .collect::<Vec<_>>();
+        assert_eq!(sums.iter().sum::<usize>(), image.data.iter().sum::<usize>());
+        {
+            let tiles = image.par_tiles_mut(WIDTH, 1);
+            assert_eq!((tiles.width(), tiles.height()), (1, HEIGHT));
+        }
+        let mut view = image.view_mut(3, 2, 10, 10).unwrap();
+        ParallelIterator::for_each(view.par_tiles_mut(3, 3).into_inner(), |mut tile| {
+            for value in tile.pix_iter_serialized_mut().into_inner() {
+                *value = 0;
+            }
+        });
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(image.get(x, y) == Some(&0), (3..13).contains(&x) && (2..12).contains(&y));
+            }
+        }
+    }
+
+    #[test]
+    fn rows_columns() {
+        const WIDTH: usize = 13;
+        const HEIGHT: usize = 7;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        image.par_rows_mut().for_each(|(y, row)| {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = y * WIDTH + x;
+            }
+        });
+        assert_eq!(image.data, (0..WIDTH * HEIGHT).collect::<Vec<_>>());
+        assert_eq!(image.rows().len(), HEIGHT);
+        for (y, row) in image.rows() {
+            assert_eq!(row, &image.data[y * WIDTH..(y + 1) * WIDTH]);
+        }
+        assert_eq!(image.par_rows().map(|(_, row)| row.len()).sum::<usize>(), WIDTH * HEIGHT);
+        for (y, row) in image.rows_mut().rev() {
+            row.reverse();
+            assert_eq!(row[0], y * WIDTH + WIDTH - 1);
+        }
+        for (y, row) in image.rows_mut() {
+            row.reverse();
+            assert_eq!(row[0], y * WIDTH);
+        }
+
+        assert_eq!(image.columns().len(), WIDTH);
+        for (x, column) in image.columns() {
+            assert_eq!((column.width(), column.height()), (1, HEIGHT));
+            assert_eq!(
+                (0..HEIGHT).map(|y| *column.get(0, y).unwrap()).collect::<Vec<_>>(),
+                (0..HEIGHT).map(|y| y * WIDTH + x).collect::<Vec<_>>()
+            );
+        }
+        let sums = image
+            .par_columns()
+            .map(|(_, column)| ParallelIterator::sum::<usize>(column.pix_iter().into_inner()))
+            .collect::<Vec<_>>();
+        assert_eq!(sums, (0..WIDTH).map(|x| (0..HEIGHT).map(|y| y * WIDTH + x).sum::<usize>()).collect::<Vec<_>>());
+        image.par_columns_mut().for_each(|(x, mut column)| {
+            for value in column.pix_iter_serialized_mut().into_inner() {
+                *value += x;
+            }
+        });
+        for (x, mut column) in image.columns_mut() {
+            *column.get_mut(0, 0).unwrap() -= x;
+        }
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(image.get(x, y), Some(&(y * WIDTH + x + if y == 0 { 0 } else { x })));
+            }
+        }
+    }
+
+    #[test]
+    fn coords() {
+        const WIDTH: usize = 17;
+        const HEIGHT: usize = 9;
+        let image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| y * WIDTH + x);
+        assert_eq!(image.data, (0..WIDTH * HEIGHT).collect::<Vec<_>>());
+        let image = PhysicalImage::from_fn(ConstUsize::<3>::new(), 2, |x, y| (x, y));
+        assert_eq!(image.data, vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+
+        let image = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| x * y);
+        let iter = image.pix_iter().with_coords();
+        assert_eq!(iter.width(), WIDTH);
+        assert_eq!(iter.into_inner().len(), WIDTH * HEIGHT);
+        let coords = image.pix_iter().with_coords().collect_image();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(coords.get(x, y), Some(&((x, y), &(x * y))));
+            }
+        }
+        let coords = image.pix_iter_serialized().with_coords();
+        assert_eq!(coords.into_inner().len(), WIDTH * HEIGHT);
+        let coords = image.pix_iter_serialized().with_coords().collect_image();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(coords.get(x, y), Some(&((x, y), &(x * y))));
+            }
+        }
+        let view = image.view(3, 2, 5, 4).unwrap();
+        let coords = view.pix_iter().with_coords().into_inner().map(|((x, y), value)| (x, y, *value)).collect::<Vec<_>>();
+        assert_eq!(coords, (0..4).flat_map(|y| (0..5).map(move |x| (x, y, (x + 3) * (y + 2)))).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn aligned() {
+        const WIDTH: usize = 13;
+        const HEIGHT: usize = 7;
+        fn check<T>(image: &PhysicalImage<T>, alignment: usize) {
+            assert!(image.stride() >= WIDTH);
+            assert_eq!(image.stride() * std::mem::size_of::<T>() % alignment, 0);
+            for (y, row) in image.rows() {
+                assert_eq!(row.len(), WIDTH);
+                assert_eq!(row.as_ptr() as usize % alignment, 0, "row {} is not aligned", y);
+                assert_eq!(row.as_ptr(), image.as_ptr().wrapping_add(y * image.stride()));
+            }
+        }
+        check(&PhysicalImage::<u8>::new_aligned(WIDTH, HEIGHT, 64), 64);
+        check(&PhysicalImage::<f32>::new_aligned(WIDTH, HEIGHT, 32), 32);
+        check(&PhysicalImage::with_default_aligned(WIDTH, HEIGHT, Rgb([0u8; 3]), 16), 16);
+        check(&PhysicalImage::<u64>::new_aligned(WIDTH, HEIGHT, 4), 4);
+
+        let mut image = PhysicalImage::with_default_aligned(WIDTH, HEIGHT, 0usize, 64);
+        assert_ne!(image.stride(), WIDTH);
+        image.pix_iter_mut().with_coords().into_inner().for_each(|((x, y), value)| *value = y * WIDTH + x);
+        let expect = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| y * WIDTH + x);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                assert_eq!(image.get(x, y), Some(&(y * WIDTH + x)));
+            }
+        }
+        assert_eq!(image.view(3, 2, 4, 4).unwrap().get(1, 1), Some(&(3 * WIDTH + 4)));
+        assert_eq!(image.pix_iter().map(|v| *v).collect_image().data, expect.data);
+        assert_eq!(image.pix_iter_serialized().map(|v| *v).collect_image().data, expect.data);
+        assert_eq!(
+            image.rotate90().pix_iter().map(|v| *v).collect_image().data,
+            expect.rotate90().pix_iter().map(|v| *v).collect_image().data
+        );
+        assert_eq!(Iterator::sum::<usize>(image.pix_iter_serialized().into_inner()), expect.data.iter().sum::<usize>());
+
+        let mut copy = PhysicalImage::with_default_aligned(WIDTH, HEIGHT, 0usize, 32);
+        image.pix_iter().map(|v| *v).collect_into(&mut copy);
+        assert_eq!(copy.into_pix_iter_serialized().into_inner().collect::<Vec<_>>(), expect.data);
+        assert_eq!(image.into_pix_iter().collect_image().data, expect.data);
+
+        let mut rgb = PhysicalImage::with_default_aligned(5, 3, Rgb([0u8; 3]), 64);
+        *rgb.get_mut(4, 2).unwrap() = Rgb([1, 2, 3]);
+        let buffer: ImageBuffer<Rgb<u8>, Vec<u8>> = rgb.into();
+        assert_eq!((buffer.width(), buffer.height()), (5, 3));
+        assert_eq!(buffer.get_pixel(4, 2), &Rgb([1, 2, 3]));
+        assert_eq!(buffer.get_pixel(3, 2), &Rgb([0, 0, 0]));
+
+        let empty = PhysicalImage::<u8>::new_aligned(0, HEIGHT, 64);
+        assert_eq!(empty.into_pix_iter().collect_image().data, Vec::<u8>::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment 48 is not a power of two")]
+    fn aligned_not_power_of_two() {
+        PhysicalImage::<u8>::new_aligned(4, 4, 48);
+    }
+
+    #[test]
+    #[should_panic(expected = "alignment 4 cannot be reached by elements of 2 bytes aligned to 1")]
+    fn aligned_unreachable() {
+        PhysicalImage::<[u8; 2]>::new_aligned(0, 4, 4);
+    }
+
+    #[test]
+    fn adaptors() {
+        const WIDTH: usize = 13;
+        const HEIGHT: usize = 7;
+        let a = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| x + y * WIDTH);
+        let b = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| x * y);
+        let c = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, _| x % 3 == 0);
+
+        let doubled = a.pix_iter().map(|v| v * 2).collect_image();
+        assert_eq!(doubled.data, a.data.iter().map(|v| v * 2).collect::<Vec<_>>());
+        let doubled = a.pix_iter_serialized().map(|v| v * 2).collect_image();
+        assert_eq!(doubled.data, a.data.iter().map(|v| v * 2).collect::<Vec<_>>());
+
+        let sum = a.pix_iter().zip(b.pix_iter()).map(|(a, b)| a + b).collect_image();
+        assert_eq!(sum.data, a.data.iter().zip(b.data.iter()).map(|(a, b)| a + b).collect::<Vec<_>>());
+        let sum = a.pix_iter_serialized().zip(b.pix_iter_serialized()).map(|(a, b)| a + b).collect_image();
+        assert_eq!(sum.data, a.data.iter().zip(b.data.iter()).map(|(a, b)| a + b).collect::<Vec<_>>());
+
+        let select = |(a, b, c): (&usize, &usize, &bool)| if *c { *a } else { *b };
+        let selected = a.pix_iter().zip3(b.pix_iter(), c.pix_iter()).map(select).collect_image();
+        let expect = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| if x % 3 == 0 { x + y * WIDTH } else { x * y });
+        assert_eq!(selected.data, expect.data);
+        let selected = a.pix_iter_serialized().zip3(b.pix_iter_serialized(), c.pix_iter_serialized()).map(select).collect_image();
+        assert_eq!(selected.data, expect.data);
+
+        let (even, odd) = a.pix_iter().map(|v| (v * 2, v * 2 + 1)).unzip_images();
+        assert_eq!((even.width, even.height, odd.width, odd.height), (WIDTH, HEIGHT, WIDTH, HEIGHT));
+        assert_eq!(odd.data, a.data.iter().map(|v| v * 2 + 1).collect::<Vec<_>>());
+        let (even, odd) = a.pix_iter_serialized().map(|v| (v * 2, v * 2 + 1)).unzip_images();
+        assert_eq!(even.data, a.data.iter().map(|v| v * 2).collect::<Vec<_>>());
+        assert_eq!(odd.data, a.data.iter().map(|v| v * 2 + 1).collect::<Vec<_>>());
+
+        let filtered = a.pix_iter().filter_map_or(0, |v| if v % 2 == 0 { Some(*v) } else { None }).collect_image();
+        assert_eq!(filtered.data, a.data.iter().map(|v| if v % 2 == 0 { *v } else { 0 }).collect::<Vec<_>>());
+        let filtered = a.pix_iter_serialized().filter_map_or(0, |v| if v % 2 == 0 { Some(*v) } else { None }).collect_image();
+        assert_eq!(filtered.data, a.data.iter().map(|v| if v % 2 == 0 { *v } else { 0 }).collect::<Vec<_>>());
+
+        let a = PhysicalImage::<u8, _, _>::new(ConstUsize::<4>::new(), 3);
+        let b = PhysicalImage::<u8, _, _>::new(4, ConstUsize::<3>::new());
+        let zipped = a.pix_iter().zip(b.pix_iter());
+        let _: (ConstUsize<4>, ConstUsize<3>) = (zipped.width(), zipped.height());
+    }
+
+    #[test]
+    fn collect_into() {
+        const WIDTH: usize = 11;
+        const HEIGHT: usize = 6;
+        let source = PhysicalImage::from_fn(WIDTH, HEIGHT, |x, y| x * 100 + y);
+        let expect = source.data.iter().map(|v| v + 1).collect::<Vec<_>>();
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        source.pix_iter().map(|v| v + 1).collect_into(&mut image);
+        assert_eq!(image.data, expect);
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        source.pix_iter_serialized().map(|v| v + 1).collect_into(&mut image);
+        assert_eq!(image.data, expect);
+
+        let mut canvas = PhysicalImage::new(WIDTH + 5, HEIGHT + 4);
+        let mut view = canvas.view_mut(2, 3, WIDTH, HEIGHT).unwrap();
+        source.pix_iter().map(|v| v + 1).collect_into_view(&mut view);
+        let mut view = canvas.view_mut(4, 0, WIDTH, HEIGHT).unwrap();
+        source.pix_iter_serialized().map(|v| v + 2).collect_into_view(&mut view);
+        for y in 0..HEIGHT + 4 {
+            for x in 0..WIDTH + 5 {
+                let expect = if (4..4 + WIDTH).contains(&x) && y < HEIGHT {
+                    (x - 4) * 100 + y + 2
+                } else if (2..2 + WIDTH).contains(&x) && (3..3 + HEIGHT).contains(&y) {
+                    (x - 2) * 100 + (y - 3) + 1
+                } else {
+                    0
+                };
+                assert_eq!(canvas.get(x, y), Some(&expect), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "widths of zipped images differ")]
+    fn collect_into_size_mismatch() {
+        let source = PhysicalImage::<u8>::new(4, 3);
+        let mut image = PhysicalImage::<u8>::new(3, 4);
+        source.pix_iter().map(|v| *v).collect_into(&mut image);
+    }
+
+    #[test]
+    #[should_panic(expected = "widths of zipped images differ")]
+    fn zip_size_mismatch() {
+        let a = PhysicalImage::<u8>::new(4, 3);
+        let b = PhysicalImage::<u8>::new(3, 4);
+        a.pix_iter().zip(b.pix_iter());
+    }
+
     #[test]
     fn image_buffer() {
         const WIDTH: usize = 50;
@@ -1,6 +1,8 @@
-use partial_const::MayBeConst;
+use partial_const::{Equals, MayBeConst};
+use rayon::iter::{Map, ZipEq};
 use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
 
+use crate::image_ref::ImageRefMut;
 use crate::physical_image::PhysicalImage;
 use crate::{IntoPixelIterator, IntoSerializedPixelIterator};
 
@@ -35,6 +37,93 @@ impl<I: ParallelIterator + IndexedParallelIterator, W: MayBeConst<usize>, H: May
         iter.collect_into_vec(&mut data);
         PhysicalImage::with_data(width, height, data)
     }
+
+    /// Write pixels into an existing image instead of allocating a new one.
+    /// # Panics
+    /// Panics if sizes of this iterator and image differ. If both sizes are constant, it is checked at compile time.
+    pub fn collect_into<IW, IH>(self, image: &mut PhysicalImage<I::Item, IW, IH>)
+    where
+        I::Item: Send,
+        IW: MayBeConst<usize> + Equals<W>,
+        IH: MayBeConst<usize> + Equals<H>,
+    {
+        image.pix_iter_mut().zip(self).into_inner().for_each(|(dst, src)| *dst = src);
+    }
+
+    /// Write pixels into an existing view, e.g. a sub-rectangle of another image.
+    /// # Panics
+    /// Panics if sizes of this iterator and view differ. If both sizes are constant, it is checked at compile time.
+    pub fn collect_into_view<'a, VW, VH>(self, view: &mut ImageRefMut<'a, I::Item, VW, VH>)
+    where
+        I::Item: Send + 'a,
+        VW: MayBeConst<usize> + Equals<W>,
+        VH: MayBeConst<usize> + Equals<H>,
+    {
+        view.pix_iter_mut().zip(self).into_inner().for_each(|(dst, src)| *dst = src);
+    }
+
+    /// Attach coordinates (x, y) of each pixel to the items.
+    pub fn with_coords(self) -> PixIter<impl IndexedParallelIterator<Item = ((usize, usize), I::Item)>, W, H> {
+        let PixIter { width, height, iter } = self;
+        let w = width.value();
+        PixIter::new(iter.enumerate().map(move |(i, item)| ((i % w, i / w), item)), width, height)
+    }
+
+    /// Apply f to each pixel.
+    pub fn map<R: Send, F: Fn(I::Item) -> R + Sync + Send>(self, f: F) -> PixIter<Map<I, F>, W, H> {
+        let PixIter { width, height, iter } = self;
+        PixIter::new(iter.map(f), width, height)
+    }
+
+    /// Apply f to each pixel, using default where f returns None.
+    pub fn filter_map_or<R: Clone + Send + Sync, F: Fn(I::Item) -> Option<R> + Sync + Send>(self, default: R, f: F) -> PixIter<impl IndexedParallelIterator<Item = R>, W, H> {
+        self.map(move |item| f(item).unwrap_or_else(|| default.clone()))
+    }
+
+    /// Iterate pairs of pixels at the same location of two images.
+    /// # Panics
+    /// Panics if sizes of two images differ. If both sizes are constant, it is checked at compile time.
+    pub fn zip<P: IntoPixelIterator>(self, other: P) -> PixIter<ZipEq<I, P::Iter>, W::ConstSide, H::ConstSide>
+    where
+        W: Equals<P::Width>,
+        H: Equals<P::Height>,
+    {
+        let other = other.into_pix_iter();
+        let width = self.width.get_const_side(&other.width).expect("widths of zipped images differ");
+        let height = self.height.get_const_side(&other.height).expect("heights of zipped images differ");
+        PixIter::new(self.iter.zip_eq(other.iter), width, height)
+    }
+
+    /// Iterate triples of pixels at the same location of three images.
+    /// # Panics
+    /// Panics if sizes of three images differ. If all sizes are constant, it is checked at compile time.
+    #[allow(clippy::type_complexity)]
+    pub fn zip3<P1: IntoPixelIterator, P2: IntoPixelIterator>(
+        self,
+        other1: P1,
+        other2: P2,
+    ) -> PixIter<impl IndexedParallelIterator<Item = (I::Item, P1::Item, P2::Item)>, <W::ConstSide as Equals<P2::Width>>::ConstSide, <H::ConstSide as Equals<P2::Height>>::ConstSide>
+    where
+        P1::Item: Send,
+        P2::Item: Send,
+        W: Equals<P1::Width>,
+        H: Equals<P1::Height>,
+        W::ConstSide: Equals<P2::Width>,
+        H::ConstSide: Equals<P2::Height>,
+    {
+        self.zip(other1).zip(other2).map(|((a, b), c)| (a, b, c))
+    }
+}
+
+impl<A: Send, B: Send, I: ParallelIterator<Item = (A, B)> + IndexedParallelIterator, W: MayBeConst<usize>, H: MayBeConst<usize>> PixIter<I, W, H> {
+    /// Collect pairs of pixels into two images.
+    pub fn unzip_images(self) -> (PhysicalImage<A, W, H>, PhysicalImage<B, W, H>) {
+        let PixIter { width, height, iter } = self;
+        let mut a = Vec::with_capacity(width.value() * height.value());
+        let mut b = Vec::with_capacity(width.value() * height.value());
+        iter.unzip_into_vecs(&mut a, &mut b);
+        (PhysicalImage::with_data(width, height, a), PhysicalImage::with_data(width, height, b))
+    }
 }
 
 impl<I: ParallelIterator + IndexedParallelIterator, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoPixelIterator for PixIter<I, W, H> {
@@ -77,6 +166,89 @@ impl<I: ExactSizeIterator, W: MayBeConst<usize>, H: MayBeConst<usize>> Serialize
         let data = iter.collect();
         PhysicalImage::with_data(width, height, data)
     }
+
+    /// Write pixels into an existing image instead of allocating a new one.
+    /// # Panics
+    /// Panics if sizes of this iterator and image differ. If both sizes are constant, it is checked at compile time.
+    pub fn collect_into<IW, IH>(self, image: &mut PhysicalImage<I::Item, IW, IH>)
+    where
+        IW: MayBeConst<usize> + Equals<W>,
+        IH: MayBeConst<usize> + Equals<H>,
+    {
+        image.pix_iter_serialized_mut().zip(self).into_inner().for_each(|(dst, src)| *dst = src);
+    }
+
+    /// Write pixels into an existing view, e.g. a sub-rectangle of another image.
+    /// # Panics
+    /// Panics if sizes of this iterator and view differ. If both sizes are constant, it is checked at compile time.
+    pub fn collect_into_view<'a, VW, VH>(self, view: &mut ImageRefMut<'a, I::Item, VW, VH>)
+    where
+        I::Item: 'a,
+        VW: MayBeConst<usize> + Equals<W>,
+        VH: MayBeConst<usize> + Equals<H>,
+    {
+        view.pix_iter_serialized_mut().zip(self).into_inner().for_each(|(dst, src)| *dst = src);
+    }
+
+    /// Attach coordinates (x, y) of each pixel to the items.
+    pub fn with_coords(self) -> SerializePixIter<impl ExactSizeIterator<Item = ((usize, usize), I::Item)>, W, H> {
+        let SerializePixIter { width, height, iter } = self;
+        let w = width.value();
+        SerializePixIter::new(iter.enumerate().map(move |(i, item)| ((i % w, i / w), item)), width, height)
+    }
+
+    /// Apply f to each pixel.
+    pub fn map<R, F: FnMut(I::Item) -> R>(self, f: F) -> SerializePixIter<std::iter::Map<I, F>, W, H> {
+        let SerializePixIter { width, height, iter } = self;
+        SerializePixIter::new(iter.map(f), width, height)
+    }
+
+    /// Apply f to each pixel, using default where f returns None.
+    pub fn filter_map_or<R: Clone, F: FnMut(I::Item) -> Option<R>>(self, default: R, mut f: F) -> SerializePixIter<impl ExactSizeIterator<Item = R>, W, H> {
+        self.map(move |item| f(item).unwrap_or_else(|| default.clone()))
+    }
+
+    /// Iterate pairs of pixels at the same location of two images.
+    /// # Panics
+    /// Panics if sizes of two images differ. If both sizes are constant, it is checked at compile time.
+    pub fn zip<P: IntoSerializedPixelIterator>(self, other: P) -> SerializePixIter<std::iter::Zip<I, P::Iter>, W::ConstSide, H::ConstSide>
+    where
+        W: Equals<P::Width>,
+        H: Equals<P::Height>,
+    {
+        let other = other.into_pix_iter_serialized();
+        let width = self.width.get_const_side(&other.width).expect("widths of zipped images differ");
+        let height = self.height.get_const_side(&other.height).expect("heights of zipped images differ");
+        assert_eq!(self.iter.len(), other.iter.len(), "lengths of zipped iterators differ");
+        SerializePixIter::new(self.iter.zip(other.iter), width, height)
+    }
+
+    /// Iterate triples of pixels at the same location of three images.
+    /// # Panics
+    /// Panics if sizes of three images differ. If all sizes are constant, it is checked at compile time.
+    #[allow(clippy::type_complexity)]
+    pub fn zip3<P1: IntoSerializedPixelIterator, P2: IntoSerializedPixelIterator>(
+        self,
+        other1: P1,
+        other2: P2,
+    ) -> SerializePixIter<impl ExactSizeIterator<Item = (I::Item, P1::Item, P2::Item)>, <W::ConstSide as Equals<P2::Width>>::ConstSide, <H::ConstSide as Equals<P2::Height>>::ConstSide>
+    where
+        W: Equals<P1::Width>,
+        H: Equals<P1::Height>,
+        W::ConstSide: Equals<P2::Width>,
+        H::ConstSide: Equals<P2::Height>,
+    {
+        self.zip(other1).zip(other2).map(|((a, b), c)| (a, b, c))
+    }
+}
+
+impl<A, B, I: ExactSizeIterator<Item = (A, B)>, W: MayBeConst<usize>, H: MayBeConst<usize>> SerializePixIter<I, W, H> {
+    /// Collect pairs of pixels into two images.
+    pub fn unzip_images(self) -> (PhysicalImage<A, W, H>, PhysicalImage<B, W, H>) {
+        let SerializePixIter { width, height, iter } = self;
+        let (a, b) = iter.unzip();
+        (PhysicalImage::with_data(width, height, a), PhysicalImage::with_data(width, height, b))
+    }
 }
 
 impl<I: ExactSizeIterator, W: MayBeConst<usize>, H: MayBeConst<usize>> IntoSerializedPixelIterator for SerializePixIter<I, W, H> {
@@ -0,0 +1,519 @@
+//! Rank filters such as the median filter.
+
+use std::cmp::Ordering;
+
+use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator, ParallelSliceMut};
+
+use crate::image_ref::ImageRef;
+use crate::physical_image::PhysicalImage;
+use crate::{ReadPixel, View};
+
+/// Windows with at most this number of pixels are sorted by a sorting network.
+const SORTING_NETWORK_MAX: usize = 25;
+/// Number of output rows processed by a task of the histogram algorithm.
+const BAND_HEIGHT: usize = 32;
+
+/// A pixel type which can be ranked by rank filters.
+pub trait RankPixel: Copy + Default + Send + Sync + PartialOrd {
+    /// Number of bits of histogram bins for the constant-time algorithm, or None if values are ranked by sorting.
+    const HISTOGRAM_BITS: Option<u32>;
+    /// Get histogram bin of this value. This is called only if HISTOGRAM_BITS is Some.
+    fn to_bin(self) -> usize;
+    /// Get value of histogram bin. This is called only if HISTOGRAM_BITS is Some.
+    fn from_bin(bin: usize) -> Self;
+}
+
+macro_rules! impl_rank_histogram {
+    ($($t:ty => $bits:expr;)*) => {
+        $(
+            impl RankPixel for $t {
+                const HISTOGRAM_BITS: Option<u32> = Some($bits);
+
+                fn to_bin(self) -> usize {
+                    (self as isize - <$t>::MIN as isize) as usize
+                }
+
+                fn from_bin(bin: usize) -> Self {
+                    (bin as isize + <$t>::MIN as isize) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_rank_histogram! {
+    u8 => 8;
+    i8 => 8;
+    u16 => 16;
+    i16 => 16;
+}
+
+macro_rules! impl_rank_sort {
+    ($($t:ty),*) => {
+        $(
+            impl RankPixel for $t {
+                const HISTOGRAM_BITS: Option<u32> = None;
+
+                fn to_bin(self) -> usize {
+                    unreachable!("{} is ranked by sorting", stringify!($t))
+                }
+
+                fn from_bin(_: usize) -> Self {
+                    unreachable!("{} is ranked by sorting", stringify!($t))
+                }
+            }
+        )*
+    };
+}
+
+impl_rank_sort!(u32, i32, u64, i64, f32, f64);
+
+/// Get comparators of Batcher's merge exchange sorting network for n values (Knuth, Algorithm 5.2.2M).
+fn sorting_network(n: usize) -> Vec<(usize, usize)> {
+    let mut comparators = Vec::new();
+    if n < 2 {
+        return comparators;
+    }
+    let t = usize::BITS - (n - 1).leading_zeros();
+    let mut p = 1 << (t - 1);
+    while p > 0 {
+        let (mut q, mut r, mut d) = (1 << (t - 1), 0, p);
+        loop {
+            comparators.extend((0..n - d).filter(|i| i & p == r).map(|i| (i, i + d)));
+            if q == p {
+                break;
+            }
+            d = q - p;
+            q >>= 1;
+            r = p;
+        }
+        p >>= 1;
+    }
+    comparators
+}
+
+/// Get the index of the value at percentile in count sorted values.
+fn rank_index(percentile: f64, count: usize) -> usize {
+    (percentile / 100.0 * (count - 1) as f64).round() as usize
+}
+
+/// Clip the range of length from begin to 0..size.
+fn clip(begin: isize, length: usize, size: usize) -> (usize, usize) {
+    (begin.clamp(0, size as isize) as usize, (begin + length as isize).clamp(0, size as isize) as usize)
+}
+
+/// Rank filter which sorts each window.
+/// The output has size (w, h), and the width x height window of output pixel (x, y) begins at (x - shift_x, y - shift_y) of source.
+fn sorted<T: RankPixel>(source: ImageRef<'_, T>, (w, h): (usize, usize), (shift_x, shift_y): (isize, isize), (width, height): (usize, usize), percentile: f64) -> PhysicalImage<T> {
+    let network = sorting_network(width * height);
+    let mut data = Vec::with_capacity(w * h);
+    (0..w * h)
+        .into_par_iter()
+        .map_init(
+            || Vec::with_capacity(width * height),
+            |values, i| {
+                let (x, y) = (i % w, i / w);
+                let (begin_x, end_x) = clip(x as isize - shift_x, width, source.width());
+                let (begin_y, end_y) = clip(y as isize - shift_y, height, source.height());
+                values.clear();
+                values.extend(
+                    (begin_y..end_y)
+                        .flat_map(|y| (begin_x..end_x).map(move |x| (x, y)))
+                        .map(|(x, y)| unsafe { *source.get_unchecked(x, y) }),
+                );
+                if values.is_empty() {
+                    return T::default();
+                }
+                let index = rank_index(percentile, values.len());
+                if values.len() == width * height && values.len() <= SORTING_NETWORK_MAX {
+                    for &(a, b) in network.iter() {
+                        if values[b] < values[a] {
+                            values.swap(a, b);
+                        }
+                    }
+                    values[index]
+                } else {
+                    *values.select_nth_unstable_by(index, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal)).1
+                }
+            },
+        )
+        .collect_into_vec(&mut data);
+    PhysicalImage::with_data(w, h, data)
+}
+
+/// Marks a fine segment of a column histogram which is not allocated yet.
+const UNALLOCATED: u32 = u32::MAX;
+
+/// Two-level histograms of adjacent columns. Coarse bins are counted for every column,
+/// and fine segments of fine bins in a coarse bin are allocated from a pool only while the coarse bin is not empty,
+/// so that a column holds at most as many fine segments as it has pixels in the window.
+struct ColumnHistograms {
+    fine_bits: u32,
+    fine_columns: bool,
+    coarse: usize,
+    coarse_counts: Vec<u32>,
+    slots: Vec<u32>,
+    pool: Vec<u32>,
+    free: Vec<u32>,
+}
+
+impl ColumnHistograms {
+    /// Fine segments are not counted if fine_columns is false.
+    fn new(bits: u32, fine_columns: bool) -> Self {
+        let fine_bits = bits / 2;
+        ColumnHistograms {
+            fine_bits,
+            fine_columns,
+            coarse: 1 << (bits - fine_bits),
+            coarse_counts: Vec::new(),
+            slots: Vec::new(),
+            pool: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Empty histograms and resize them to columns, keeping allocated memory.
+    fn reset(&mut self, columns: usize) {
+        self.coarse_counts.clear();
+        self.coarse_counts.resize(columns * self.coarse, 0);
+        self.slots.clear();
+        self.slots.resize(columns * self.coarse, UNALLOCATED);
+        self.pool.clear();
+        self.free.clear();
+    }
+
+    fn update(&mut self, column: usize, bin: usize, added: bool) {
+        let fine = 1 << self.fine_bits;
+        let segment = column * self.coarse + (bin >> self.fine_bits);
+        let delta = if added { 1 } else { u32::MAX };
+        self.coarse_counts[segment] = self.coarse_counts[segment].wrapping_add(delta);
+        if !self.fine_columns {
+            return;
+        }
+        if self.slots[segment] == UNALLOCATED {
+            self.slots[segment] = self.free.pop().unwrap_or_else(|| {
+                self.pool.resize(self.pool.len() + fine, 0);
+                ((self.pool.len() >> self.fine_bits) - 1) as u32
+            });
+        }
+        let count = &mut self.pool[((self.slots[segment] as usize) << self.fine_bits) + (bin & (fine - 1))];
+        *count = count.wrapping_add(delta);
+        if self.coarse_counts[segment] == 0 {
+            self.free.push(self.slots[segment]);
+            self.slots[segment] = UNALLOCATED;
+        }
+    }
+
+    fn coarse(&self, column: usize) -> &[u32] {
+        &self.coarse_counts[column * self.coarse..(column + 1) * self.coarse]
+    }
+
+    fn fine(&self, column: usize, segment: usize) -> Option<&[u32]> {
+        match self.slots[column * self.coarse + segment] {
+            UNALLOCATED => None,
+            slot => Some(&self.pool[(slot as usize) << self.fine_bits..((slot as usize) + 1) << self.fine_bits]),
+        }
+    }
+}
+
+/// Find the bin which holds the value at index of counted values, and reduce index by counts of preceding bins.
+/// Bins are skipped by blocks of 16 to make the scan short.
+fn find_bin(counts: &[u32], index: &mut u32) -> usize {
+    let mut bin = 0;
+    for block in counts.chunks(16) {
+        let sum = block.iter().sum::<u32>();
+        if *index < sum {
+            break;
+        }
+        *index -= sum;
+        bin += block.len();
+    }
+    while *index >= counts[bin] {
+        *index -= counts[bin];
+        bin += 1;
+    }
+    bin
+}
+
+/// Rank filter by the algorithm of Perreault and Hebert: histograms of columns are updated once per row,
+/// and the window histogram slides along the row adding and removing whole column histograms.
+/// Fine bins of the window histogram are updated lazily per coarse bin.
+/// Rows are split into bands processed in parallel, and each band into strips of columns so that coarse histograms of columns stay small.
+/// Output and windows are placed as in sorted.
+fn histogram<T: RankPixel>(source: ImageRef<'_, T>, (w, h): (usize, usize), (shift_x, shift_y): (isize, isize), (width, height): (usize, usize), percentile: f64, bits: u32) -> PhysicalImage<T> {
+    let (source_width, source_height) = (source.width(), source.height());
+    let fine_bits = bits / 2;
+    let fine = 1 << fine_bits;
+    let coarse = 1 << (bits - fine_bits);
+    // Fine bins of a column in a short window are counted faster from its pixels than from its fine segment.
+    let fine_columns = height * 32 >= fine;
+    let strip_width = ((1 << 16) / coarse).max(1);
+    let mut image = unsafe { PhysicalImage::new_uninit(w, h) };
+    if w == 0 {
+        return image;
+    }
+    image.data.par_chunks_mut(w * BAND_HEIGHT).enumerate().for_each(|(band, rows)| {
+        let band_y = band * BAND_HEIGHT;
+        let mut columns = ColumnHistograms::new(bits, fine_columns);
+        let mut window_coarse = vec![0u32; coarse];
+        let mut window_fine = vec![0u32; 1 << bits];
+        let mut updated_at: Vec<Option<(usize, usize)>> = vec![None; coarse];
+        for strip_x in (0..w).step_by(strip_width) {
+            let strip_end = (strip_x + strip_width).min(w);
+            let (column_begin, column_end) = clip(strip_x as isize - shift_x, strip_end - strip_x + width - 1, source_width);
+            columns.reset(column_end - column_begin);
+            let update = |columns: &mut ColumnHistograms, y: usize, added: bool| {
+                for x in column_begin..column_end {
+                    columns.update(x - column_begin, unsafe { *source.get_unchecked(x, y) }.to_bin(), added);
+                }
+            };
+            let (row_begin, row_end) = clip(band_y as isize - shift_y, height, source_height);
+            (row_begin..row_end).for_each(|y| update(&mut columns, y, true));
+            let mut previous = (row_begin, row_end);
+            for (row_index, row) in rows.chunks_mut(w).enumerate() {
+                let y = band_y + row_index;
+                let (row_begin, row_end) = clip(y as isize - shift_y, height, source_height);
+                (previous.0..row_begin).for_each(|y| update(&mut columns, y, false));
+                (previous.1..row_end).for_each(|y| update(&mut columns, y, true));
+                previous = (row_begin, row_end);
+                let mut window = clip(strip_x as isize - shift_x, width, source_width);
+                window_coarse.iter_mut().for_each(|count| *count = 0);
+                for x in window.0..window.1 {
+                    window_coarse.iter_mut().zip(columns.coarse(x - column_begin)).for_each(|(a, b)| *a += b);
+                }
+                updated_at.iter_mut().for_each(|updated_at| *updated_at = None);
+                for (x, value) in row.iter_mut().enumerate().take(strip_end).skip(strip_x) {
+                    let next = clip(x as isize - shift_x, width, source_width);
+                    for column in window.0..next.0 {
+                        window_coarse.iter_mut().zip(columns.coarse(column - column_begin)).for_each(|(a, b)| *a -= b);
+                    }
+                    for column in window.1..next.1 {
+                        window_coarse.iter_mut().zip(columns.coarse(column - column_begin)).for_each(|(a, b)| *a += b);
+                    }
+                    window = next;
+                    let count = (window.1 - window.0) * (row_end - row_begin);
+                    let pointer: *mut T = value;
+                    if count == 0 {
+                        unsafe { pointer.write(T::default()) };
+                        continue;
+                    }
+                    let mut index = rank_index(percentile, count) as u32;
+                    let segment = find_bin(&window_coarse, &mut index);
+                    let updated = updated_at[segment].filter(|&(begin, end)| begin <= window.0 && window.0 <= end);
+                    let window_segment = &mut window_fine[segment * fine..(segment + 1) * fine];
+                    if updated.is_none() {
+                        window_segment.iter_mut().for_each(|count| *count = 0);
+                    }
+                    let mut add = |column: usize, added: bool| match columns.fine(column - column_begin, segment) {
+                        Some(column) if added => window_segment.iter_mut().zip(column).for_each(|(a, b)| *a += b),
+                        Some(column) => window_segment.iter_mut().zip(column).for_each(|(a, b)| *a -= b),
+                        None if !fine_columns => {
+                            for y in row_begin..row_end {
+                                let bin = unsafe { *source.get_unchecked(column, y) }.to_bin();
+                                if bin >> fine_bits == segment {
+                                    let count = &mut window_segment[bin & (fine - 1)];
+                                    *count = if added { *count + 1 } else { *count - 1 };
+                                }
+                            }
+                        }
+                        None => {}
+                    };
+                    let (begin, end) = updated.unwrap_or((window.0, window.0));
+                    (begin..window.0).for_each(|column| add(column, false));
+                    (end..window.1).for_each(|column| add(column, true));
+                    updated_at[segment] = Some(window);
+                    let window_segment = &window_fine[segment * fine..(segment + 1) * fine];
+                    let bin = find_bin(window_segment, &mut index);
+                    unsafe { pointer.write(T::from_bin(segment * fine + bin)) };
+                }
+            }
+        }
+    });
+    image
+}
+
+/// Replace each pixel by the value at percentile (0 to 100) of the pixels in the width x height window centered on it,
+/// i.e. the value at index round(percentile / 100 * (n - 1)) of the n sorted values.
+/// Pixels of a window which are outside of source or not valid in source are not counted,
+/// and pixels whose window holds no valid pixel are set to the default value.
+/// 8-bit and 16-bit values are ranked by a constant-time histogram algorithm, and other values by sorting.
+/// # Panics
+/// Panics if percentile is not in 0 to 100, or the window is empty.
+pub fn rank_filter<S>(source: &S, width: usize, height: usize, percentile: f64) -> PhysicalImage<S::Item>
+where
+    S: View,
+    S::Item: RankPixel,
+{
+    assert!((0.0..=100.0).contains(&percentile), "percentile {} is not in 0 to 100", percentile);
+    assert!(width > 0 && height > 0, "window is empty");
+    let size = (source.width(), source.height());
+    let valid = source.valid_rect();
+    if valid.w == 0 || valid.h == 0 {
+        return PhysicalImage::from_fn(size.0, size.1, |_, _| S::Item::default());
+    }
+    let shift = ((width / 2 + valid.x) as isize, (height / 2 + valid.y) as isize);
+    let valid = source.view(valid.x, valid.y, valid.w, valid.h).expect("valid area of source should be a valid view");
+    match S::Item::HISTOGRAM_BITS {
+        Some(bits) if width * height > SORTING_NETWORK_MAX => histogram(valid, size, shift, (width, height), percentile, bits),
+        _ => sorted(valid, size, shift, (width, height), percentile),
+    }
+}
+
+/// Replace each pixel by the median of the pixels in the width x height window centered on it.
+/// See rank_filter for details.
+pub fn median_filter<S>(source: &S, width: usize, height: usize) -> PhysicalImage<S::Item>
+where
+    S: View,
+    S::Item: RankPixel,
+{
+    rank_filter(source, width, height, 50.0)
+}
+
+/// Replace each pixel by the minimum of the pixels in the width x height window centered on it.
+/// See rank_filter for details.
+pub fn min_filter<S>(source: &S, width: usize, height: usize) -> PhysicalImage<S::Item>
+where
+    S: View,
+    S::Item: RankPixel,
+{
+    rank_filter(source, width, height, 0.0)
+}
+
+/// Replace each pixel by the maximum of the pixels in the width x height window centered on it.
+/// See rank_filter for details.
+pub fn max_filter<S>(source: &S, width: usize, height: usize) -> PhysicalImage<S::Item>
+where
+    S: View,
+    S::Item: RankPixel,
+{
+    rank_filter(source, width, height, 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::physical_image::PhysicalImage;
+    use crate::rank::{max_filter, median_filter, min_filter, rank_filter, sorting_network, RankPixel};
+    use crate::{ReadPixel, View};
+
+    fn naive<S: ReadPixel>(image: &S, width: usize, height: usize, percentile: f64) -> Vec<S::Item>
+    where
+        S::Item: RankPixel,
+    {
+        let mut result = Vec::new();
+        for y in 0..image.height() as isize {
+            for x in 0..image.width() as isize {
+                let mut values = Vec::new();
+                for j in y - (height / 2) as isize..y - (height / 2) as isize + height as isize {
+                    for i in x - (width / 2) as isize..x - (width / 2) as isize + width as isize {
+                        if i >= 0 && j >= 0 {
+                            values.extend(image.get(i as usize, j as usize).copied());
+                        }
+                    }
+                }
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                result.push(if values.is_empty() {
+                    Default::default()
+                } else {
+                    values[(percentile / 100.0 * (values.len() - 1) as f64).round() as usize]
+                });
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn network() {
+        for n in 0..=12 {
+            let network = sorting_network(n);
+            for bits in 0..1u32 << n {
+                let mut values = (0..n).map(|i| bits >> i & 1).collect::<Vec<_>>();
+                for &(a, b) in network.iter() {
+                    if values[b] < values[a] {
+                        values.swap(a, b);
+                    }
+                }
+                assert!(values.windows(2).all(|w| w[0] <= w[1]), "{}", n);
+            }
+        }
+    }
+
+    #[test]
+    fn rank() {
+        let image = PhysicalImage::from_fn(41, 37, |x, y| ((x * 37 + y * 101 + x * y * 7) % 251) as u8);
+        let windows = [(1, 1), (3, 3), (5, 5), (4, 2), (7, 5), (9, 9), (2, 15), (45, 3)];
+        for &(width, height) in windows.iter() {
+            for &percentile in [0.0, 25.0, 50.0, 90.0, 100.0].iter() {
+                let expect = naive(&image, width, height, percentile);
+                assert_eq!(
+                    rank_filter(&image, width, height, percentile).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                    expect,
+                    "{}x{} {}",
+                    width,
+                    height,
+                    percentile
+                );
+            }
+        }
+        let wide = PhysicalImage::from_fn(37, 70, |x, y| ((x * 7919 + y * 104729 + x * y * 31) % 65536) as u16);
+        for &(width, height) in [(3, 3), (7, 7), (11, 3), (5, 9), (3, 21)].iter() {
+            assert_eq!(
+                median_filter(&wide, width, height).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                naive(&wide, width, height, 50.0),
+                "{}x{}",
+                width,
+                height
+            );
+        }
+        let signed = PhysicalImage::from_fn(29, 40, |x, y| ((x * 7919 + y * 104729 + x * y * 31) % 65536) as u16 as i16);
+        for &(width, height) in [(7, 7), (5, 9), (2, 4)].iter() {
+            assert_eq!(
+                median_filter(&signed, width, height).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                naive(&signed, width, height, 50.0),
+                "{}x{}",
+                width,
+                height
+            );
+        }
+        let small = PhysicalImage::from_fn(23, 19, |x, y| ((x * 37 + y * 101) % 256) as u8 as i8);
+        assert_eq!(
+            rank_filter(&small, 9, 5, 30.0).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            naive(&small, 9, 5, 30.0)
+        );
+        let float = PhysicalImage::from_fn(13, 11, |x, y| ((x * 37 + y * 101) % 23) as f32 * 0.5);
+        assert_eq!(min_filter(&float, 3, 3).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), naive(&float, 3, 3, 0.0));
+        assert_eq!(max_filter(&float, 6, 5).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), naive(&float, 6, 5, 100.0));
+
+        let view = image.view(5, 3, 20, 30).unwrap();
+        let copied = PhysicalImage::with_data(20, 30, view.pix_iter_serialized().into_inner().copied().collect());
+        assert_eq!(median_filter(&view, 7, 7).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), naive(&copied, 7, 7, 50.0));
+
+        for &(x, y, width, height) in [(-3, -2, 47, 41), (30, 20, 20, 25), (-20, 10, 15, 5), (50, 0, 4, 4)].iter() {
+            let overhang = image.view_overhang(x, y, width, height);
+            for &(width, height) in [(3, 3), (7, 5), (9, 9)].iter() {
+                assert_eq!(
+                    median_filter(&overhang, width, height).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                    naive(&overhang, width, height, 50.0),
+                    "{}x{} at ({}, {})",
+                    width,
+                    height,
+                    x,
+                    y
+                );
+            }
+            let overhang = wide.view_overhang(x, y, width, height);
+            assert_eq!(
+                max_filter(&overhang, 7, 7).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                naive(&overhang, 7, 7, 100.0),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "percentile 101 is not in 0 to 100")]
+    fn rank_percentile_out_of_range() {
+        rank_filter(&PhysicalImage::<u8>::new(3, 3), 3, 3, 101.0);
+    }
+}
@@ -0,0 +1,309 @@
+//! Morphological reconstruction and operations built from it.
+
+use std::collections::VecDeque;
+
+use partial_const::MayBeConst;
+
+use crate::morphology::MorphologyPixel;
+use crate::physical_image::PhysicalImage;
+use crate::{Connectivity, ReadPixel};
+
+fn packed<T: Copy, W: MayBeConst<usize>, H: MayBeConst<usize>>(image: &PhysicalImage<T, W, H>) -> Vec<T> {
+    image.pix_iter_serialized().into_inner().copied().collect()
+}
+
+/// Reconstruct marker under mask by geodesic dilation with join = max and meet = min, or by geodesic erosion with the dual.
+/// This is the hybrid algorithm of Vincent: a raster scan, an anti-raster scan and a FIFO queue propagation.
+fn reconstruct<T: MorphologyPixel + PartialEq>(mut marker: Vec<T>, mask: &[T], width: usize, height: usize, connectivity: Connectivity, join: fn(T, T) -> T, meet: fn(T, T) -> T) -> Vec<T> {
+    let below = |a: T, b: T| a != b && join(a, b) == b;
+    let preceding = connectivity.preceding();
+    marker.iter_mut().zip(mask.iter()).for_each(|(marker, &mask)| *marker = meet(*marker, mask));
+    for i in 0..marker.len() {
+        let value = preceding
+            .iter()
+            .filter_map(|&offset| Connectivity::neighbor(i, offset, width, height))
+            .fold(marker[i], |value, j| join(value, marker[j]));
+        marker[i] = meet(value, mask[i]);
+    }
+    let mut queue = VecDeque::new();
+    for i in (0..marker.len()).rev() {
+        let following = || preceding.iter().filter_map(|&(dx, dy)| Connectivity::neighbor(i, (-dx, -dy), width, height));
+        let value = following().fold(marker[i], |value, j| join(value, marker[j]));
+        marker[i] = meet(value, mask[i]);
+        if following().any(|j| below(marker[j], marker[i]) && below(marker[j], mask[j])) {
+            queue.push_back(i);
+        }
+    }
+    while let Some(i) = queue.pop_front() {
+        for &offset in connectivity.neighbors() {
+            if let Some(j) = Connectivity::neighbor(i, offset, width, height) {
+                if below(marker[j], marker[i]) && marker[j] != mask[j] {
+                    marker[j] = meet(marker[i], mask[j]);
+                    queue.push_back(j);
+                }
+            }
+        }
+    }
+    marker
+}
+
+fn assert_same_size<T, U, W: MayBeConst<usize>, H: MayBeConst<usize>>(a: &PhysicalImage<T, W, H>, b: &PhysicalImage<U, W, H>) {
+    assert!(a.width() == b.width() && a.height() == b.height(), "sizes of marker and mask differ");
+}
+
+/// Reconstruct marker by repeated geodesic dilation under mask until it is stable.
+/// The result is the largest image below mask whose regional maxima are seeded by marker.
+/// # Panics
+/// Panics if sizes of marker and mask differ.
+pub fn reconstruct_by_dilation<T, W, H>(marker: &PhysicalImage<T, W, H>, mask: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<T, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    assert_same_size(marker, mask);
+    let data = reconstruct(packed(marker), &packed(mask), mask.width(), mask.height(), connectivity, T::max, T::min);
+    PhysicalImage::with_data(mask.width, mask.height, data)
+}
+
+/// Reconstruct marker by repeated geodesic erosion above mask until it is stable.
+/// This is the dual of reconstruct_by_dilation.
+/// # Panics
+/// Panics if sizes of marker and mask differ.
+pub fn reconstruct_by_erosion<T, W, H>(marker: &PhysicalImage<T, W, H>, mask: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<T, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    assert_same_size(marker, mask);
+    let data = reconstruct(packed(marker), &packed(mask), mask.width(), mask.height(), connectivity, T::min, T::max);
+    PhysicalImage::with_data(mask.width, mask.height, data)
+}
+
+/// Get the marker which has the value of image on its border and value elsewhere.
+fn border_marker<T: MorphologyPixel, W: MayBeConst<usize>, H: MayBeConst<usize>>(image: &PhysicalImage<T, W, H>, value: T) -> PhysicalImage<T, W, H> {
+    let (width, height) = (image.width(), image.height());
+    let data = image
+        .pix_iter_serialized()
+        .with_coords()
+        .into_inner()
+        .map(|((x, y), &pixel)| if x == 0 || y == 0 || x + 1 == width || y + 1 == height { pixel } else { value })
+        .collect();
+    PhysicalImage::with_data(image.width, image.height, data)
+}
+
+/// Fill holes, i.e. dark regions which are not connected to the border of image.
+/// For binary images, background components not reachable from the border become true.
+/// For grayscale images, basins are raised to the lowest level through which they connect to the border.
+pub fn fill_holes<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<T, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    reconstruct_by_erosion(&border_marker(image, T::MAX), image, connectivity)
+}
+
+/// Remove bright structures which are connected to the border of image.
+/// For binary images, foreground components touching the border become false.
+/// For grayscale images, the reconstruction from the border is subtracted from image.
+pub fn clear_border<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<T, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let reconstructed = reconstruct_by_dilation(&border_marker(image, T::MIN), image, connectivity);
+    let data = packed(image).into_iter().zip(packed(&reconstructed)).map(|(a, b)| a.difference(b)).collect();
+    PhysicalImage::with_data(image.width, image.height, data)
+}
+
+/// Mark connected plateaus of equal values which have no neighbor beyond them, where beyond(a, value) is a != value && join(a, value) == a.
+fn regional_extrema<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity, join: fn(T, T) -> T) -> PhysicalImage<bool, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let (width, height) = (image.width(), image.height());
+    let data = packed(image);
+    let mut visited = vec![false; data.len()];
+    let mut result = vec![false; data.len()];
+    let mut plateau = Vec::new();
+    for start in 0..data.len() {
+        if visited[start] {
+            continue;
+        }
+        let value = data[start];
+        let mut extremum = true;
+        visited[start] = true;
+        plateau.clear();
+        plateau.push(start);
+        let mut index = 0;
+        while index < plateau.len() {
+            let i = plateau[index];
+            index += 1;
+            for &offset in connectivity.neighbors() {
+                if let Some(j) = Connectivity::neighbor(i, offset, width, height) {
+                    if data[j] == value {
+                        if !visited[j] {
+                            visited[j] = true;
+                            plateau.push(j);
+                        }
+                    } else if join(data[j], value) == data[j] {
+                        extremum = false;
+                    }
+                }
+            }
+        }
+        if extremum {
+            plateau.iter().for_each(|&i| result[i] = true);
+        }
+    }
+    PhysicalImage::with_data(image.width, image.height, result)
+}
+
+/// Mark regional maxima, i.e. connected plateaus whose neighbors are all lower.
+/// A constant image is a single regional maximum.
+pub fn regional_maxima<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<bool, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    regional_extrema(image, connectivity, T::max)
+}
+
+/// Mark regional minima, i.e. connected plateaus whose neighbors are all higher.
+/// A constant image is a single regional minimum.
+pub fn regional_minima<T, W, H>(image: &PhysicalImage<T, W, H>, connectivity: Connectivity) -> PhysicalImage<bool, W, H>
+where
+    T: MorphologyPixel + PartialEq,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    regional_extrema(image, connectivity, T::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::morphology::{dilate, StructuringElement};
+    use crate::physical_image::PhysicalImage;
+    use crate::reconstruction::{clear_border, fill_holes, reconstruct_by_dilation, reconstruct_by_erosion, regional_maxima, regional_minima};
+    use crate::{Connectivity, ReadPixel};
+
+    fn parse(rows: &[&str]) -> PhysicalImage<u8> {
+        PhysicalImage::with_data(rows[0].len(), rows.len(), rows.iter().flat_map(|row| row.bytes().map(|c| c - b'0')).collect())
+    }
+
+    fn naive_reconstruct(marker: &PhysicalImage<u8>, mask: &PhysicalImage<u8>, element: &StructuringElement) -> PhysicalImage<u8> {
+        let mut current = marker.pix_iter().zip(mask.pix_iter()).map(|(&a, &b)| Ord::min(a, b)).collect_image();
+        loop {
+            let next = dilate(&current, element).pix_iter().zip(mask.pix_iter()).map(|(&a, &b)| Ord::min(a, b)).collect_image();
+            if next.pix_iter_serialized().into_inner().eq(current.pix_iter_serialized().into_inner()) {
+                return current;
+            }
+            current = next;
+        }
+    }
+
+    #[test]
+    fn reconstruction() {
+        let mask = PhysicalImage::from_fn(17, 11, |x, y| ((x * 37 + y * 101 + x * y * 7) % 10) as u8);
+        let marker = PhysicalImage::from_fn(17, 11, |x, y| if (x * 5 + y * 3) % 13 == 0 { 9 } else { 0 });
+        let elements = [
+            (Connectivity::Four, StructuringElement::Cross { width: 3, height: 3 }),
+            (Connectivity::Eight, StructuringElement::Rectangle { width: 3, height: 3 }),
+        ];
+        for (connectivity, element) in elements.iter() {
+            let expect = naive_reconstruct(&marker, &mask, element);
+            assert_eq!(
+                reconstruct_by_dilation(&marker, &mask, *connectivity).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                expect.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+            );
+            let invert = |image: &PhysicalImage<u8>| image.pix_iter().map(|&v| 9 - v).collect_image();
+            let eroded = reconstruct_by_erosion(&invert(&marker), &invert(&mask), *connectivity);
+            assert_eq!(
+                eroded.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+                invert(&expect).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+            );
+        }
+
+        let blobs = PhysicalImage::from_fn(8, 3, |x, y| x != 3 && (x, y) != (6, 1));
+        let seed = PhysicalImage::from_fn(8, 3, |x, y| (x, y) == (0, 0));
+        assert_eq!(
+            reconstruct_by_dilation(&seed, &blobs, Connectivity::Four)
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            PhysicalImage::from_fn(8, 3, |x, _| x < 3).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "sizes of marker and mask differ")]
+    fn reconstruction_size_mismatch() {
+        reconstruct_by_dilation(&PhysicalImage::<u8>::new(3, 3), &PhysicalImage::<u8>::new(3, 4), Connectivity::Four);
+    }
+
+    #[test]
+    fn fill_clear() {
+        let ring = parse(&["000000", "011100", "010100", "011100", "000001"]).pix_iter().map(|&v| v == 1).collect_image();
+        let filled = fill_holes(&ring, Connectivity::Four);
+        assert_eq!(filled.get(2, 2), Some(&true));
+        assert_eq!(filled.get(0, 0), Some(&false));
+        let cleared = clear_border(&ring, Connectivity::Eight);
+        assert_eq!(cleared.pix_iter_serialized().into_inner().filter(|&&v| v).count(), 8);
+        assert_eq!(cleared.get(5, 4), Some(&false));
+        let diagonal = parse(&["000", "010", "001"]).pix_iter().map(|&v| v == 1).collect_image();
+        assert_eq!(
+            clear_border(&diagonal, Connectivity::Four).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![false, false, false, false, true, false, false, false, false]
+        );
+        assert_eq!(
+            clear_border(&diagonal, Connectivity::Eight).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![false; 9]
+        );
+
+        let basin = parse(&["55555", "52225", "52015", "52225", "55455"]);
+        assert_eq!(
+            fill_holes(&basin, Connectivity::Four).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            parse(&["55555", "54445", "54445", "54445", "55455"]).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        let peaks = parse(&["11111", "13331", "13931", "13331", "11111"]);
+        assert_eq!(
+            clear_border(&peaks, Connectivity::Four).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            parse(&["00000", "02220", "02820", "02220", "00000"]).pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn extrema() {
+        let image = parse(&["1122", "1302", "1332", "0002"]);
+        let maxima = regional_maxima(&image, Connectivity::Four);
+        assert_eq!(
+            maxima.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![false, false, false, false, false, true, false, false, false, true, true, false, false, false, false, false]
+        );
+        let minima = regional_minima(&image, Connectivity::Four);
+        assert_eq!(
+            minima.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![false, false, false, false, false, false, true, false, false, false, false, false, true, true, true, false]
+        );
+        let minima = regional_minima(&image, Connectivity::Eight);
+        assert_eq!(
+            minima.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![false, false, false, false, false, false, true, false, false, false, false, false, true, true, true, false]
+        );
+        assert_eq!(
+            regional_maxima(&PhysicalImage::with_default(3, 2, 7u8), Connectivity::Eight)
+                .pix_iter_serialized()
+                .into_inner()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![true; 6]
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! Properties of labeled regions.
+
+use std::collections::HashMap;
+
+use partial_const::MayBeConst;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::physical_image::PhysicalImage;
+use crate::{ReadPixel, Rectangle};
+
+/// Properties of a region of a label image. Pixel (x, y) is centered at (x, y).
+#[derive(Debug, Clone)]
+pub struct RegionProperties {
+    /// Label of the region.
+    pub label: u32,
+    /// Number of pixels.
+    pub area: usize,
+    /// Smallest rectangle which contains the region.
+    pub bounding_box: Rectangle,
+    /// Centroid (x, y).
+    pub centroid: (f64, f64),
+    /// Raw moments; moments[p][q] is the sum of x^p * y^q for p + q <= 3, and 0 otherwise.
+    pub moments: [[f64; 4]; 4],
+    /// Central moments about centroid, indexed like moments.
+    pub central_moments: [[f64; 4]; 4],
+    /// Seven Hu moments, invariant to translation, scale and rotation.
+    pub hu_moments: [f64; 7],
+    /// Angle in radians from x axis to the major axis, in -PI/2 to PI/2. Since y axis points down, positive angles are clockwise.
+    pub orientation: f64,
+    /// Eccentricity of the ellipse with the same second central moments, 0 for a circle and approaching 1 for a line.
+    pub eccentricity: f64,
+    /// Number of pixel edges between the region and other pixels or outside of image.
+    pub perimeter: usize,
+    /// Ratio of area to area of the convex hull of pixel squares.
+    pub solidity: f64,
+    /// Mean of intensity image in the region, if an intensity image is given.
+    pub mean_intensity: Option<f64>,
+}
+
+#[derive(Clone)]
+struct Accumulator {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+    /// First pixel found, about which moments are accumulated so that they stay small for regions far from the origin.
+    origin: (usize, usize),
+    /// Raw moments about origin.
+    moments: [[i128; 4]; 4],
+    perimeter: usize,
+    intensity: f64,
+    /// Leftmost and rightmost x of each row (y, left, right) in ascending order of y within a task.
+    rows: Vec<(usize, usize, usize)>,
+}
+
+impl Accumulator {
+    fn new(x: usize, y: usize) -> Self {
+        Accumulator {
+            left: x,
+            top: y,
+            right: x,
+            bottom: y,
+            origin: (x, y),
+            moments: [[0; 4]; 4],
+            perimeter: 0,
+            intensity: 0.0,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add pixels x in begin..end of row y.
+    fn push_run(&mut self, y: usize, begin: usize, end: usize) {
+        self.left = self.left.min(begin);
+        self.right = self.right.max(end - 1);
+        self.top = self.top.min(y);
+        self.bottom = self.bottom.max(y);
+        let mut x_powers = [0i128; 4];
+        for x in begin..end {
+            let x = x as i128 - self.origin.0 as i128;
+            x_powers.iter_mut().fold(1, |power, sum| {
+                *sum += power;
+                power * x
+            });
+        }
+        let dy = y as i128 - self.origin.1 as i128;
+        for (p, row) in self.moments.iter_mut().enumerate() {
+            let mut y_power = 1;
+            for moment in row.iter_mut().take(4 - p) {
+                *moment += x_powers[p] * y_power;
+                y_power *= dy;
+            }
+        }
+        match self.rows.last_mut() {
+            Some(row) if row.0 == y => {
+                row.1 = row.1.min(begin);
+                row.2 = row.2.max(end - 1);
+            }
+            _ => self.rows.push((y, begin, end - 1)),
+        }
+    }
+
+    fn merge(&mut self, other: Accumulator) {
+        self.left = self.left.min(other.left);
+        self.top = self.top.min(other.top);
+        self.right = self.right.max(other.right);
+        self.bottom = self.bottom.max(other.bottom);
+        let offset = (other.origin.0 as i128 - self.origin.0 as i128, other.origin.1 as i128 - self.origin.1 as i128);
+        let moments = shift(&other.moments, offset);
+        self.moments.iter_mut().flatten().zip(moments.iter().flatten()).for_each(|(a, b)| *a += b);
+        self.perimeter += other.perimeter;
+        self.intensity += other.intensity;
+        self.rows.extend(other.rows);
+    }
+}
+
+/// Get area of the convex hull of points by the monotone chain algorithm and the shoelace formula.
+fn convex_hull_area(mut points: Vec<(i64, i64)>) -> f64 {
+    points.sort_unstable();
+    points.dedup();
+    let cross = |o: (i64, i64), a: (i64, i64), b: (i64, i64)| (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0);
+    let mut hull: Vec<(i64, i64)> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        for &point in points.iter() {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop();
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+    let doubled = (0..hull.len()).map(|i| {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        a.0 * b.1 - b.0 * a.1
+    });
+    doubled.sum::<i64>().abs() as f64 / 2.0
+}
+
+fn binomial(n: usize, k: usize) -> i128 {
+    [[1, 0, 0, 0], [1, 1, 0, 0], [1, 2, 1, 0], [1, 3, 3, 1]][n][k]
+}
+
+/// Move raw moments about a point p to moments about p - offset, exactly.
+fn shift(moments: &[[i128; 4]; 4], offset: (i128, i128)) -> [[i128; 4]; 4] {
+    let mut shifted = [[0; 4]; 4];
+    for (p, row) in shifted.iter_mut().enumerate() {
+        for (q, shifted) in row.iter_mut().enumerate().take(4 - p) {
+            for (i, moments) in moments.iter().enumerate().take(p + 1) {
+                for (j, moment) in moments.iter().enumerate().take(q + 1) {
+                    *shifted += binomial(p, i) * binomial(q, j) * offset.0.pow((p - i) as u32) * offset.1.pow((q - j) as u32) * moment;
+                }
+            }
+        }
+    }
+    shifted
+}
+
+fn finish(label: u32, accumulator: Accumulator, intensity: bool) -> RegionProperties {
+    let mut moments = [[0.0; 4]; 4];
+    let absolute = shift(&accumulator.moments, (accumulator.origin.0 as i128, accumulator.origin.1 as i128));
+    moments.iter_mut().flatten().zip(absolute.iter().flatten()).for_each(|(a, &b)| *a = b as f64);
+    let area = accumulator.moments[0][0] as usize;
+    let m = |p: usize, q: usize| accumulator.moments[p][q] as f64;
+    let relative = (m(1, 0) / m(0, 0), m(0, 1) / m(0, 0));
+    let centroid = (accumulator.origin.0 as f64 + relative.0, accumulator.origin.1 as f64 + relative.1);
+    let mut central_moments = [[0.0; 4]; 4];
+    for (p, row) in central_moments.iter_mut().enumerate() {
+        for (q, central) in row.iter_mut().enumerate().take(4 - p) {
+            for i in 0..=p {
+                for j in 0..=q {
+                    *central += (binomial(p, i) * binomial(q, j)) as f64 * (-relative.0).powi((p - i) as i32) * (-relative.1).powi((q - j) as i32) * m(i, j);
+                }
+            }
+        }
+    }
+    let mu = central_moments;
+    let eta = |p: usize, q: usize| mu[p][q] / mu[0][0].powf(1.0 + (p + q) as f64 / 2.0);
+    let (n20, n02, n11, n30, n03, n21, n12) = (eta(2, 0), eta(0, 2), eta(1, 1), eta(3, 0), eta(0, 3), eta(2, 1), eta(1, 2));
+    let (a, b) = (n30 + n12, n21 + n03);
+    let hu_moments = [
+        n20 + n02,
+        (n20 - n02).powi(2) + 4.0 * n11 * n11,
+        (n30 - 3.0 * n12).powi(2) + (3.0 * n21 - n03).powi(2),
+        a * a + b * b,
+        (n30 - 3.0 * n12) * a * (a * a - 3.0 * b * b) + (3.0 * n21 - n03) * b * (3.0 * a * a - b * b),
+        (n20 - n02) * (a * a - b * b) + 4.0 * n11 * a * b,
+        (3.0 * n21 - n03) * a * (a * a - 3.0 * b * b) - (n30 - 3.0 * n12) * b * (3.0 * a * a - b * b),
+    ];
+    let (mean, difference) = ((mu[2][0] + mu[0][2]) / 2.0, (mu[2][0] - mu[0][2]) / 2.0);
+    let root = (difference * difference + mu[1][1] * mu[1][1]).sqrt();
+    let (major, minor) = (mean + root, mean - root);
+    let points = accumulator
+        .rows
+        .iter()
+        .flat_map(|&(y, left, right)| {
+            let (y, left, right) = (y as i64, left as i64, right as i64 + 1);
+            [(left, y), (left, y + 1), (right, y), (right, y + 1)]
+        })
+        .collect();
+    RegionProperties {
+        label,
+        area,
+        bounding_box: Rectangle {
+            x: accumulator.left,
+            y: accumulator.top,
+            w: accumulator.right - accumulator.left + 1,
+            h: accumulator.bottom - accumulator.top + 1,
+        },
+        centroid,
+        moments,
+        central_moments,
+        hu_moments,
+        orientation: 0.5 * (2.0 * mu[1][1]).atan2(mu[2][0] - mu[0][2]),
+        eccentricity: if major > 0.0 { (1.0 - minor / major).max(0.0).sqrt() } else { 0.0 },
+        perimeter: accumulator.perimeter,
+        solidity: area as f64 / convex_hull_area(points),
+        mean_intensity: if intensity { Some(accumulator.intensity / area as f64) } else { None },
+    }
+}
+
+/// Accumulate regions of labels row by row in parallel, with an accumulator map for each task.
+fn properties(labels: &[u32], intensity: Option<&[f64]>, width: usize, height: usize) -> Vec<RegionProperties> {
+    let maps = (0..height)
+        .into_par_iter()
+        .fold(HashMap::<u32, Accumulator>::new, |mut map, y| {
+            let row = &labels[y * width..(y + 1) * width];
+            let mut begin = 0;
+            while begin < width {
+                let label = row[begin];
+                let end = row[begin..].iter().position(|&other| other != label).map_or(width, |length| begin + length);
+                if label != 0 {
+                    let accumulator = map.entry(label).or_insert_with(|| Accumulator::new(begin, y));
+                    accumulator.push_run(y, begin, end);
+                    let differs = |y: Option<usize>, x: usize| y.and_then(|y| labels.get(y * width + x)).map_or(true, |&other| other != label);
+                    accumulator.perimeter += 2
+                        + (begin..end)
+                            .map(|x| differs(y.checked_sub(1), x) as usize + differs(Some(y + 1).filter(|&y| y < height), x) as usize)
+                            .sum::<usize>();
+                    if let Some(intensity) = intensity {
+                        accumulator.intensity += intensity[y * width + begin..y * width + end].iter().sum::<f64>();
+                    }
+                }
+                begin = end;
+            }
+            map
+        })
+        .collect::<Vec<_>>();
+    let mut merged = HashMap::<u32, Accumulator>::new();
+    for (label, accumulator) in maps.into_iter().flatten() {
+        match merged.get_mut(&label) {
+            Some(merged) => merged.merge(accumulator),
+            None => {
+                merged.insert(label, accumulator);
+            }
+        }
+    }
+    let mut regions = merged.into_iter().collect::<Vec<_>>();
+    regions.sort_unstable_by_key(|(label, _)| *label);
+    regions.into_par_iter().map(|(label, accumulator)| finish(label, accumulator, intensity.is_some())).collect()
+}
+
+/// Compute properties of each region of nonzero labels in ascending order of labels.
+/// Pixels of a region need not be connected.
+pub fn region_properties<W: MayBeConst<usize>, H: MayBeConst<usize>>(labels: &PhysicalImage<u32, W, H>) -> Vec<RegionProperties> {
+    let data = labels.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>();
+    properties(&data, None, labels.width(), labels.height())
+}
+
+/// Compute properties of each region of nonzero labels in ascending order of labels, with mean_intensity of intensity.
+/// # Panics
+/// Panics if sizes of labels and intensity differ.
+pub fn region_properties_with_intensity<T, W, H>(labels: &PhysicalImage<u32, W, H>, intensity: &PhysicalImage<T, W, H>) -> Vec<RegionProperties>
+where
+    T: Copy + Into<f64> + Send + Sync,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    assert!(labels.width() == intensity.width() && labels.height() == intensity.height(), "sizes of labels and intensity differ");
+    let data = labels.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>();
+    let intensity = intensity.pix_iter_serialized().into_inner().map(|&value| value.into()).collect::<Vec<_>>();
+    properties(&data, Some(&intensity), labels.width(), labels.height())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::label::label_components;
+    use crate::physical_image::PhysicalImage;
+    use crate::region::{region_properties, region_properties_with_intensity};
+    use crate::{Connectivity, ReadPixel};
+
+    fn parse(rows: &[&str]) -> PhysicalImage<u32> {
+        PhysicalImage::with_data(rows[0].len(), rows.len(), rows.iter().flat_map(|row| row.bytes().map(|c| (c - b'0') as u32)).collect())
+    }
+
+    #[test]
+    fn properties() {
+        let labels = parse(&["000000", "011100", "011102", "000022", "300000"]);
+        let intensity = PhysicalImage::from_fn(6, 5, |x, y| (x + y * 10) as u8);
+        let regions = region_properties_with_intensity(&labels, &intensity);
+        assert_eq!(
+            regions.iter().map(|region| (region.label, region.area, region.perimeter)).collect::<Vec<_>>(),
+            vec![(1, 6, 10), (2, 3, 8), (3, 1, 4)]
+        );
+
+        let rectangle = &regions[0];
+        assert_eq!((rectangle.bounding_box.x, rectangle.bounding_box.y, rectangle.bounding_box.w, rectangle.bounding_box.h), (1, 1, 3, 2));
+        assert_eq!(rectangle.centroid, (2.0, 1.5));
+        assert_eq!((rectangle.moments[1][0], rectangle.moments[0][1], rectangle.moments[2][1]), (12.0, 9.0, 42.0));
+        assert!((rectangle.central_moments[2][0] - 4.0).abs() < 1e-9 && (rectangle.central_moments[0][2] - 1.5).abs() < 1e-9);
+        assert!(rectangle.central_moments[1][1].abs() < 1e-9 && rectangle.central_moments[3][0].abs() < 1e-9);
+        assert!(rectangle.orientation.abs() < 1e-9);
+        assert!((rectangle.eccentricity - 0.625f64.sqrt()).abs() < 1e-9);
+        assert_eq!(rectangle.solidity, 1.0);
+        assert_eq!(rectangle.mean_intensity, Some(17.0));
+
+        let corner = &regions[1];
+        assert!((corner.solidity - 3.0 / 3.5).abs() < 1e-9);
+        assert!((corner.orientation + std::f64::consts::FRAC_PI_4).abs() < 1e-9);
+        assert_eq!(region_properties(&labels)[2].mean_intensity, None);
+    }
+
+    #[test]
+    fn invariance() {
+        let shape = PhysicalImage::from_fn(90, 70, |x, y| {
+            let (x, y) = (x as f64 - 40.0, y as f64 - 30.0);
+            (x * x / 400.0 + y * y / 100.0 <= 1.0 && x < 15.0) || (x - 10.0).abs() < 3.0 && (y + 12.0).abs() < 8.0
+        });
+        let transposed = PhysicalImage::from_fn(70, 90, |x, y| *shape.get(y, x).unwrap());
+        let (labels, count) = label_components(&shape, Connectivity::Four);
+        let (transposed_labels, _) = label_components(&transposed, Connectivity::Four);
+        assert_eq!(count, 1);
+        let (region, transposed_region) = (&region_properties(&labels)[0], &region_properties(&transposed_labels)[0]);
+        assert_eq!((region.area, region.perimeter), (transposed_region.area, transposed_region.perimeter));
+        for (a, b) in region.hu_moments.iter().zip(transposed_region.hu_moments.iter()).take(6) {
+            assert!((a - b).abs() <= 1e-9 * a.abs().max(1e-12), "{} {}", a, b);
+        }
+        assert!((region.hu_moments[6] + transposed_region.hu_moments[6]).abs() <= 1e-9 * region.hu_moments[6].abs().max(1e-12));
+        let turn = (region.orientation + transposed_region.orientation - std::f64::consts::FRAC_PI_2).rem_euclid(std::f64::consts::PI);
+        assert!(turn.min(std::f64::consts::PI - turn) < 1e-9, "{} {}", region.orientation, transposed_region.orientation);
+        assert!(region.solidity < 1.0 && (region.solidity - transposed_region.solidity).abs() < 1e-12);
+
+        const OFFSET: usize = 30000;
+        let far = PhysicalImage::from_fn(OFFSET + 90, 70, |x, y| (x >= OFFSET && *shape.get(x - OFFSET, y).unwrap()) as u32);
+        let far_region = &region_properties(&far)[0];
+        assert_eq!(far_region.centroid, (region.centroid.0 + OFFSET as f64, region.centroid.1));
+        for (a, b) in region.central_moments.iter().flatten().zip(far_region.central_moments.iter().flatten()) {
+            assert!((a - b).abs() <= 1e-9 * a.abs().max(1.0), "{} {}", a, b);
+        }
+        for (a, b) in region.hu_moments.iter().zip(far_region.hu_moments.iter()) {
+            assert!((a - b).abs() <= 1e-9 * a.abs().max(1e-12), "{} {}", a, b);
+        }
+    }
+}
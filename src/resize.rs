@@ -0,0 +1,200 @@
+//! Resizing images with separable filters.
+
+use std::f64::consts::PI;
+
+use partial_const::MayBeConst;
+
+use crate::border::Border;
+use crate::convolution::{par_rows, Convolve, Resolver};
+use crate::physical_image::PhysicalImage;
+use crate::ReadPixel;
+
+/// A filter to interpolate pixels in resize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Take the source pixel nearest to the center of each output pixel.
+    Nearest,
+    /// Linear interpolation of 2 pixels on each axis, widened when downscaling.
+    Bilinear,
+    /// Cubic convolution (a = -0.5) of 4 pixels on each axis, widened when downscaling.
+    Bicubic,
+    /// Lanczos windowed sinc with 3 lobes, widened when downscaling.
+    Lanczos3,
+    /// Average of source pixels covered by each output pixel, weighted by covered area.
+    Area,
+}
+
+impl Filter {
+    /// Radius of the filter at scale 1.
+    fn support(self) -> f64 {
+        match self {
+            Filter::Nearest | Filter::Area => 0.5,
+            Filter::Bilinear => 1.0,
+            Filter::Bicubic => 2.0,
+            Filter::Lanczos3 => 3.0,
+        }
+    }
+
+    pub(crate) fn weight(self, x: f64) -> f64 {
+        const A: f64 = -0.5;
+        let x = x.abs();
+        match self {
+            Filter::Nearest | Filter::Area if x < 0.5 => 1.0,
+            Filter::Bilinear => (1.0 - x).max(0.0),
+            Filter::Bicubic if x < 1.0 => ((A + 2.0) * x - (A + 3.0)) * x * x + 1.0,
+            Filter::Bicubic if x < 2.0 => ((A * x - 5.0 * A) * x + 8.0 * A) * x - 4.0 * A,
+            Filter::Lanczos3 if x < 3.0 => sinc(x) * sinc(x / 3.0),
+            _ => 0.0,
+        }
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (x * PI).sin() / (x * PI)
+    }
+}
+
+/// Compute source positions mapped by map and normalized weights for each output position on an axis.
+fn taps(filter: Filter, source_length: usize, length: usize, map: impl Fn(isize) -> Option<usize>) -> Vec<Vec<(Option<usize>, f32)>> {
+    let scale = source_length as f64 / length as f64;
+    (0..length)
+        .map(|i| {
+            let center = (i as f64 + 0.5) * scale;
+            let weights = match filter {
+                Filter::Nearest => vec![(center.floor() as isize, 1.0)],
+                Filter::Area => {
+                    let (begin, end) = (i as f64 * scale, (i + 1) as f64 * scale);
+                    (begin.floor() as isize..end.ceil() as isize).map(|j| (j, end.min(j as f64 + 1.0) - begin.max(j as f64))).collect()
+                }
+                _ => {
+                    let factor = scale.max(1.0);
+                    let support = filter.support() * factor;
+                    ((center - support).floor() as isize..=(center + support).ceil() as isize)
+                        .map(|j| (j, filter.weight((j as f64 + 0.5 - center) / factor)))
+                        .filter(|&(_, weight)| weight != 0.0)
+                        .collect::<Vec<_>>()
+                }
+            };
+            let total = weights.iter().map(|&(_, weight)| weight).sum::<f64>();
+            if total == 0.0 {
+                return vec![(map(center.floor() as isize), 1.0)];
+            }
+            weights.into_iter().map(|(j, weight)| (map(j), (weight / total) as f32)).collect()
+        })
+        .collect()
+}
+
+/// Resize source image into width x height with filter.
+/// Pixels are resampled by a horizontal pass and then a vertical pass, each in parallel.
+/// Pixels outside of valid area of source are resolved by border.
+/// Width and height can be constants, e.g. ConstUsize::<224>::new(), to get an image whose size is checked by type.
+/// # Panics
+/// Panics if border is not Constant, the output has pixels and no pixel of source is valid.
+pub fn resize<S, W, H>(source: &S, width: W, height: H, filter: Filter, border: &Border<S::Item>) -> PhysicalImage<S::Item, W, H>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let (w, h) = (width.value(), height.value());
+    let resolver = Resolver::new(source, border);
+    let columns = taps(filter, source.width(), w, |x| resolver.map_x(x));
+    let rows = taps(filter, source.height(), h, |y| resolver.map_y(y));
+    let horizontal = par_rows(w, resolver.valid_height, |row_index, row| {
+        let y = Some(resolver.valid_y + row_index);
+        for (value, taps) in row.iter_mut().zip(columns.iter()) {
+            let accumulator = taps.iter().fold(S::Item::zero(), |accumulator, &(x, weight)| resolver.get(x, y).mul_add(weight, accumulator));
+            let value: *mut <S::Item as Convolve>::Accumulator = value;
+            unsafe { value.write(accumulator) };
+        }
+    });
+    let constant_row = border.constant().map(|&constant| {
+        columns
+            .iter()
+            .map(|taps| taps.iter().fold(S::Item::zero(), |accumulator, &(_, weight)| constant.mul_add(weight, accumulator)))
+            .collect::<Vec<_>>()
+    });
+    let resized = par_rows(w, h, |y, row| {
+        for (x, value) in row.iter_mut().enumerate() {
+            let accumulator = rows[y].iter().fold(S::Item::zero(), |accumulator, &(source_y, weight)| {
+                let pixel = match source_y {
+                    Some(source_y) => horizontal.data[(source_y - resolver.valid_y) * w + x],
+                    None => constant_row.as_ref().expect("an empty image cannot be extended by this border")[x],
+                };
+                pixel.mul_add(weight, accumulator)
+            });
+            let value: *mut S::Item = value;
+            unsafe { value.write(S::Item::from_accumulator(accumulator)) };
+        }
+    });
+    PhysicalImage::with_data(width, height, resized.data)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+    use partial_const::ConstUsize;
+
+    use crate::border::Border;
+    use crate::physical_image::PhysicalImage;
+    use crate::resize::{resize, Filter};
+    use crate::{ReadPixel, View};
+
+    const FILTERS: [Filter; 5] = [Filter::Nearest, Filter::Bilinear, Filter::Bicubic, Filter::Lanczos3, Filter::Area];
+
+    #[test]
+    fn resize_values() {
+        let image = PhysicalImage::from_fn(4, 4, |x, y| (x + y * 4) as f32);
+        let area = resize(&image, 2, 2, Filter::Area, &Border::Clamp);
+        assert_eq!(area.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![2.5, 4.5, 10.5, 12.5]);
+        let nearest = resize(&image, 8, 2, Filter::Nearest, &Border::Clamp);
+        assert_eq!(nearest.get(2, 0), Some(&5.0));
+        assert_eq!(nearest.get(7, 1), Some(&15.0));
+
+        let line = PhysicalImage::with_data(2, 1, vec![0.0f32, 10.0]);
+        let linear = resize(&line, 4, 1, Filter::Bilinear, &Border::Clamp);
+        assert_eq!(linear.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![0.0, 2.5, 7.5, 10.0]);
+        let constant = resize(&line, 4, 1, Filter::Bilinear, &Border::Constant(20.0));
+        assert_eq!(constant.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![5.0, 2.5, 7.5, 12.5]);
+
+        let image = PhysicalImage::from_fn(13, 7, |x, y| ((x * 7 + y * 13) % 17) as f32);
+        for &filter in FILTERS.iter() {
+            let same = resize(&image, 13, 7, filter, &Border::Reflect);
+            for (a, b) in same.pix_iter_serialized().into_inner().zip(image.pix_iter_serialized().into_inner()) {
+                assert!((a - b).abs() < 1e-4, "{:?}: {} != {}", filter, a, b);
+            }
+            let flat = resize(&PhysicalImage::with_default(9, 5, 3.0f32), 20, 3, filter, &Border::Clamp);
+            assert!(flat.pix_iter_serialized().into_inner().all(|v| (v - 3.0).abs() < 1e-5), "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn resize_types() {
+        let image = PhysicalImage::from_fn(16, 12, |x, y| Rgb([(x * 16) as u8, (y * 20) as u8, 100]));
+        let resized: PhysicalImage<Rgb<u8>, ConstUsize<4>, ConstUsize<3>> = resize(&image, ConstUsize::<4>::new(), ConstUsize::<3>::new(), Filter::Area, &Border::Clamp);
+        assert_eq!((resized.width(), resized.height()), (4, 3));
+        assert_eq!(resized.get(1, 2), Some(&Rgb([88, 190, 100])));
+        for &filter in FILTERS.iter() {
+            let resized = resize(&image, ConstUsize::<7>::new(), 5, filter, &Border::Reflect101);
+            assert!(resized.pix_iter_serialized().into_inner().all(|pixel| pixel.0[2] == 100));
+        }
+
+        let view = image.view(4, 4, 8, 4).unwrap();
+        let resized = resize(&view, 4, 2, Filter::Area, &Border::Clamp);
+        assert_eq!(resized.get(0, 0), Some(&Rgb([72, 90, 100])));
+        let overhang = image.view_overhang(-4, 0, 20, 12);
+        let resized = resize(&overhang, 5, 3, Filter::Area, &Border::Constant(Rgb([0, 0, 0])));
+        assert_eq!(resized.get(0, 0), Some(&Rgb([0, 0, 0])));
+        assert_eq!(resized.get(1, 0), Some(&Rgb([24, 30, 100])));
+    }
+
+    #[test]
+    #[should_panic(expected = "an empty image cannot be extended")]
+    fn resize_empty_source() {
+        resize(&PhysicalImage::<f32>::new(0, 0), 2, 2, Filter::Bilinear, &Border::Clamp);
+    }
+}
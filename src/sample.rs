@@ -0,0 +1,109 @@
+//! Sampling images at fractional coordinates.
+
+use crate::border::Border;
+use crate::convolution::{Convolve, Resolver};
+use crate::resize::Filter;
+use crate::ReadPixel;
+
+/// A way to interpolate pixels at fractional coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Take the nearest pixel.
+    Nearest,
+    /// Linear interpolation of 2x2 pixels.
+    Bilinear,
+    /// Cubic convolution (a = -0.5) of 4x4 pixels.
+    Bicubic,
+}
+
+impl Interpolation {
+    /// Get the first pixel index and weights of pixels from it along an axis.
+    fn taps(self, position: f32) -> (isize, [f32; 4], usize) {
+        let floor = position.floor();
+        let fraction = position - floor;
+        let base = floor as isize;
+        match self {
+            Interpolation::Nearest => ((position + 0.5).floor() as isize, [1.0, 0.0, 0.0, 0.0], 1),
+            Interpolation::Bilinear => (base, [1.0 - fraction, fraction, 0.0, 0.0], 2),
+            Interpolation::Bicubic => {
+                let mut weights = [0.0; 4];
+                weights
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(i, weight)| *weight = Filter::Bicubic.weight(fraction as f64 + 1.0 - i as f64) as f32);
+                (base.saturating_sub(1), weights, 4)
+            }
+        }
+    }
+}
+
+/// A trait for reading values at fractional coordinates.
+pub trait Sample: ReadPixel {
+    /// Get the value at (x, y) interpolated by interpolation, where pixel (i, j) is centered at (i, j).
+    /// Pixels outside of valid area are resolved by border.
+    /// # Panics
+    /// Panics if a pixel outside of valid area is needed, the valid area is empty and border is not Border::Constant.
+    fn sample(&self, x: f32, y: f32, interpolation: Interpolation, border: &Border<Self::Item>) -> Self::Item
+    where
+        Self::Item: Convolve;
+}
+
+impl<S: ReadPixel> Sample for S {
+    fn sample(&self, x: f32, y: f32, interpolation: Interpolation, border: &Border<S::Item>) -> S::Item
+    where
+        S::Item: Convolve,
+    {
+        let resolver = Resolver::new(self, border);
+        let (begin_x, weights_x, taps_x) = interpolation.taps(x);
+        let (begin_y, weights_y, taps_y) = interpolation.taps(y);
+        if interpolation == Interpolation::Nearest {
+            return resolver.get(resolver.map_x(begin_x), resolver.map_y(begin_y));
+        }
+        let mut accumulator = S::Item::zero();
+        for (j, &weight_y) in weights_y.iter().enumerate().take(taps_y).filter(|&(_, &weight)| weight != 0.0) {
+            let source_y = resolver.map_y(begin_y.saturating_add(j as isize));
+            for (i, &weight_x) in weights_x.iter().enumerate().take(taps_x).filter(|&(_, &weight)| weight != 0.0) {
+                let source_x = resolver.map_x(begin_x.saturating_add(i as isize));
+                accumulator = resolver.get(source_x, source_y).mul_add(weight_x * weight_y, accumulator);
+            }
+        }
+        S::Item::from_accumulator(accumulator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Luma;
+
+    use crate::border::Border;
+    use crate::physical_image::PhysicalImage;
+    use crate::sample::{Interpolation, Sample};
+    use crate::View;
+
+    const INTERPOLATIONS: [Interpolation; 3] = [Interpolation::Nearest, Interpolation::Bilinear, Interpolation::Bicubic];
+
+    #[test]
+    fn sample() {
+        let image = PhysicalImage::from_fn(6, 5, |x, y| (x * 10 + y * 100) as f32);
+        for &interpolation in INTERPOLATIONS.iter() {
+            for (x, y) in [(0, 0), (3, 2), (5, 4)] {
+                let expect = (x * 10 + y * 100) as f32;
+                assert_eq!(image.sample(x as f32, y as f32, interpolation, &Border::Constant(-1.0)), expect, "{:?}", interpolation);
+            }
+            assert_eq!(image.sample(-3.0, 1.0, interpolation, &Border::Clamp), 100.0);
+        }
+        assert_eq!(image.sample(1.4, 2.6, Interpolation::Nearest, &Border::Clamp), 310.0);
+        assert!((image.sample(1.25, 2.5, Interpolation::Bilinear, &Border::Clamp) - 262.5).abs() < 1e-4);
+        assert!((image.sample(2.3, 1.7, Interpolation::Bicubic, &Border::Clamp) - 193.0).abs() < 1e-3);
+        assert_eq!(image.sample(-0.5, 0.0, Interpolation::Bilinear, &Border::Constant(-10.0)), -5.0);
+        assert_eq!(image.sample(5.5, 0.0, Interpolation::Bilinear, &Border::Reflect), 50.0);
+        assert_eq!(image.sample(f32::MAX, -1e30, Interpolation::Bicubic, &Border::Constant(7.0)), 7.0);
+
+        let overhang = image.view_overhang(-2, 0, 8, 5);
+        assert_eq!(overhang.sample(1.5, 0.0, Interpolation::Bilinear, &Border::Constant(20.0)), 10.0);
+        assert_eq!(overhang.sample(1.5, 1.0, Interpolation::Bilinear, &Border::Clamp), 100.0);
+
+        let luma = PhysicalImage::from_fn(2, 2, |x, y| Luma([(x * 100 + y * 51) as u8]));
+        assert_eq!(luma.sample(0.5, 0.5, Interpolation::Bilinear, &Border::Clamp), Luma([76]));
+    }
+}
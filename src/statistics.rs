@@ -0,0 +1,210 @@
+//! Statistics of channels.
+
+use partial_const::MayBeConst;
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
+
+use crate::pixel_iter::PixIter;
+use crate::ChannelPixel;
+
+/// Number of pixels reduced sequentially by one task.
+/// Partial results of these fixed chunks are merged in order, so results do not depend on thread count.
+const CHUNK_SIZE: usize = 4096;
+
+/// Statistics of a channel. NaN values are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelStatistics {
+    /// Number of values.
+    pub count: usize,
+    /// Sum of values.
+    pub sum: f64,
+    /// Mean of values, or NaN if there is no value.
+    pub mean: f64,
+    /// Population variance of values, or NaN if there is no value.
+    pub variance: f64,
+    /// Minimum value, or infinity if there is no value.
+    pub min: f64,
+    /// Location (x, y) of the first minimum value in row-major order.
+    pub min_location: Option<(usize, usize)>,
+    /// Maximum value, or negative infinity if there is no value.
+    pub max: f64,
+    /// Location (x, y) of the first maximum value in row-major order.
+    pub max_location: Option<(usize, usize)>,
+}
+
+impl ChannelStatistics {
+    /// Get population standard deviation of values.
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+#[derive(Clone)]
+struct Partial {
+    count: usize,
+    sum: f64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    min_location: Option<(usize, usize)>,
+    max: f64,
+    max_location: Option<(usize, usize)>,
+}
+
+impl Partial {
+    fn new() -> Self {
+        Partial {
+            count: 0,
+            sum: 0.0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            min_location: None,
+            max: f64::NEG_INFINITY,
+            max_location: None,
+        }
+    }
+
+    fn push(&mut self, value: f64, location: (usize, usize)) {
+        if value.is_nan() {
+            return;
+        }
+        self.count += 1;
+        self.sum += value;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        if self.min_location.is_none() || value < self.min {
+            self.min = value;
+            self.min_location = Some(location);
+        }
+        if self.max_location.is_none() || value > self.max {
+            self.max = value;
+            self.max_location = Some(location);
+        }
+    }
+
+    fn merge(&mut self, other: &Partial) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+        let (a, b) = (self.count as f64, other.count as f64);
+        let delta = other.mean - self.mean;
+        self.count += other.count;
+        self.sum += other.sum;
+        self.mean += delta * b / (a + b);
+        self.m2 += other.m2 + delta * delta * a * b / (a + b);
+        if other.min < self.min {
+            self.min = other.min;
+            self.min_location = other.min_location;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+            self.max_location = other.max_location;
+        }
+    }
+
+    fn finish(self) -> ChannelStatistics {
+        let (mean, variance) = if self.count == 0 { (f64::NAN, f64::NAN) } else { (self.mean, self.m2 / self.count as f64) };
+        ChannelStatistics {
+            count: self.count,
+            sum: self.sum,
+            mean,
+            variance,
+            min: self.min,
+            min_location: self.min_location,
+            max: self.max,
+            max_location: self.max_location,
+        }
+    }
+}
+
+/// Compute statistics of each channel of pixels in parallel.
+/// The result is bit-for-bit reproducible regardless of the number of threads.
+pub fn statistics<I, W, H>(iter: PixIter<I, W, H>) -> Vec<ChannelStatistics>
+where
+    I: ParallelIterator + IndexedParallelIterator,
+    I::Item: ChannelPixel,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let channels = I::Item::CHANNELS;
+    let partials = iter
+        .with_coords()
+        .into_inner()
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| {
+            let mut partials = vec![Partial::new(); channels];
+            for (location, pixel) in chunk {
+                partials.iter_mut().enumerate().for_each(|(i, partial)| partial.push(pixel.channel(i), location));
+            }
+            partials
+        })
+        .collect::<Vec<_>>();
+    let mut total = vec![Partial::new(); channels];
+    for partial in partials {
+        total.iter_mut().zip(partial.iter()).for_each(|(total, partial)| total.merge(partial));
+    }
+    total.into_iter().map(Partial::finish).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+    use rayon::ThreadPoolBuilder;
+
+    use crate::physical_image::PhysicalImage;
+    use crate::statistics::statistics;
+    use crate::View;
+
+    #[test]
+    fn statistics_values() {
+        let image = PhysicalImage::from_fn(5, 4, |x, y| Rgb([(x + y * 5) as u8, 7, if (x, y) == (3, 2) { 0 } else { 9 }]));
+        let result = statistics(image.pix_iter());
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].count, 20);
+        assert_eq!(result[0].sum, 190.0);
+        assert_eq!(result[0].mean, 9.5);
+        assert!((result[0].variance - 33.25).abs() < 1e-12);
+        assert!((result[0].std_dev() - 33.25f64.sqrt()).abs() < 1e-12);
+        assert_eq!((result[0].min, result[0].min_location), (0.0, Some((0, 0))));
+        assert_eq!((result[0].max, result[0].max_location), (19.0, Some((4, 3))));
+        assert_eq!((result[1].variance, result[1].min_location, result[1].max_location), (0.0, Some((0, 0)), Some((0, 0))));
+        assert_eq!((result[2].min, result[2].min_location), (0.0, Some((3, 2))));
+
+        let roi = image.view(1, 1, 3, 2).unwrap();
+        let result = statistics(roi.pix_iter());
+        assert_eq!(result[0].sum, (6 + 7 + 8 + 11 + 12 + 13) as f64);
+        assert_eq!((result[0].min_location, result[0].max_location), (Some((0, 0)), Some((2, 1))));
+        assert_eq!(result[2].min_location, Some((2, 1)));
+
+        let floats = PhysicalImage::from_fn(3, 1, |x, _| if x == 1 { f32::NAN } else { x as f32 });
+        let result = statistics(floats.pix_iter());
+        assert_eq!((result[0].count, result[0].mean), (2, 1.0));
+        let labels = PhysicalImage::from_fn(4, 2, |x, y| (x * 3 + y) as u32 * 100_000);
+        let result = statistics(labels.pix_iter());
+        assert_eq!((result[0].sum, result[0].max, result[0].max_location), (4_000_000.0, 1_000_000.0, Some((3, 1))));
+        let wide = statistics(PhysicalImage::from_fn(2, 1, |x, _| if x == 0 { i64::MIN } else { 0 }).pix_iter());
+        assert_eq!((wide[0].min, wide[0].min_location), (i64::MIN as f64, Some((0, 0))));
+        let empty = statistics(PhysicalImage::<f32>::new(0, 3).pix_iter());
+        assert_eq!((empty[0].count, empty[0].min_location), (0, None));
+        assert!(empty[0].mean.is_nan());
+    }
+
+    #[test]
+    fn statistics_deterministic() {
+        let image = PhysicalImage::from_fn(513, 301, |x, y| ((x * 7919 + y * 104729) % 1000) as f32 * 0.001 + 1e6);
+        let run = |threads: usize| ThreadPoolBuilder::new().num_threads(threads).build().unwrap().install(|| statistics(image.pix_iter()));
+        let expect = run(1);
+        for threads in [2, 3, 8] {
+            let result = run(threads);
+            assert_eq!(result[0].sum.to_bits(), expect[0].sum.to_bits());
+            assert_eq!(result[0].mean.to_bits(), expect[0].mean.to_bits());
+            assert_eq!(result[0].variance.to_bits(), expect[0].variance.to_bits());
+            assert_eq!(result, expect);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator};
+
+use crate::image_ref::{ImageRefMut, ImageRefOverhang};
+use crate::pixel_iter::PixIter;
+use crate::{ReadPixel, View, ViewMut};
+
+/// Iterate disjoint mutable tiles of destination in parallel, each paired with the same area of source enlarged by halo on every side.
+/// Tiles on the right and bottom edges may be smaller, and the source areas overhang where the halo crosses the edge of source.
+/// The iterator has the size of the tile grid.
+/// # Panics
+/// Panics if tile_width or tile_height is 0, sizes of source and destination differ, or any pixel of destination is not valid.
+#[allow(clippy::type_complexity)]
+pub fn par_tiles_with_halo<'s, 'd, S, D>(
+    source: &'s S,
+    destination: &'d mut D,
+    tile_width: usize,
+    tile_height: usize,
+    halo: usize,
+) -> PixIter<impl IndexedParallelIterator<Item = (ImageRefMut<'d, D::Item>, ImageRefOverhang<'s, S::Item>)>, usize, usize>
+where
+    S: View + Sync,
+    S::Item: Sync,
+    D: ViewMut,
+    D::Item: Send + Sync,
+{
+    let (width, height) = (destination.width(), destination.height());
+    assert_eq!((source.width(), source.height()), (width, height), "sizes of source and destination differ");
+    let tiles = destination
+        .view_mut(0, 0, width, height)
+        .expect("all pixels of destination must be valid")
+        .into_tiles_mut(tile_width, tile_height);
+    let (tiles_x, tiles_y) = (tiles.width(), tiles.height());
+    let iter = IndexedParallelIterator::enumerate(tiles.into_inner()).map(move |(i, tile)| {
+        let x = (i % tiles_x * tile_width) as isize - halo as isize;
+        let y = (i / tiles_x * tile_height) as isize - halo as isize;
+        let (halo_width, halo_height) = (tile.width() + 2 * halo, tile.height() + 2 * halo);
+        (tile, source.view_overhang(x, y, halo_width, halo_height))
+    });
+    PixIter::new(iter, tiles_x, tiles_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::ParallelIterator;
+
+    use crate::border::Border;
+    use crate::convolution::correlate;
+    use crate::physical_image::PhysicalImage;
+    use crate::tile::par_tiles_with_halo;
+    use crate::{ReadPixel, WritePixel};
+
+    #[test]
+    fn tiles_with_halo() {
+        const WIDTH: usize = 37;
+        const HEIGHT: usize = 29;
+        let mut image = PhysicalImage::new(WIDTH, HEIGHT);
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                *image.get_mut(x, y).unwrap() = ((x * 5 + y * 11) % 13) as f32;
+            }
+        }
+        let kernel = PhysicalImage::with_data(5, 5, (0..25).map(|i| (i % 7) as f32).collect());
+        let border = Border::Reflect101;
+        let expect = correlate(&image, &kernel, &border);
+
+        let mut result = PhysicalImage::new(WIDTH, HEIGHT);
+        let tiles = par_tiles_with_halo(&image, &mut result, 8, 6, 2);
+        assert_eq!((tiles.width(), tiles.height()), (5, 5));
+        tiles.into_inner().for_each(|(mut tile, source)| {
+            assert_eq!((source.width(), source.height()), (tile.width() + 4, tile.height() + 4));
+            let filtered = correlate(&source, &kernel, &border);
+            for y in 0..tile.height() {
+                for x in 0..tile.width() {
+                    *tile.get_mut(x, y).unwrap() = *filtered.get(x + 2, y + 2).unwrap();
+                }
+            }
+        });
+        for (result, expect) in result.data.iter().zip(expect.data.iter()) {
+            assert!((result - expect).abs() < 1e-3, "{} != {}", result, expect);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sizes of source and destination differ")]
+    fn tiles_with_halo_size_mismatch() {
+        let image = PhysicalImage::<f32>::new(10, 10);
+        let mut result = PhysicalImage::<f32>::new(10, 11);
+        par_tiles_with_halo(&image, &mut result, 4, 4, 1);
+    }
+}
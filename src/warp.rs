@@ -0,0 +1,306 @@
+//! Geometric transforms of images.
+
+use partial_const::{Equals, MayBeConst};
+use rayon::prelude::ParallelIterator;
+
+use crate::border::Border;
+use crate::convolution::Convolve;
+use crate::physical_image::PhysicalImage;
+use crate::sample::{Interpolation, Sample};
+use crate::{IntoPixelIterator, ReadPixel};
+
+/// Fill a width x height image in parallel, sampling source at position f(x, y) for each pixel (x, y).
+fn warp<S, W, H, F>(source: &S, width: W, height: H, interpolation: Interpolation, border: &Border<S::Item>, f: F) -> PhysicalImage<S::Item, W, H>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+    F: Fn(f32, f32) -> (f32, f32) + Sync + Send,
+{
+    let mut image = unsafe { PhysicalImage::new_uninit(width, height) };
+    image.pix_iter_mut().with_coords().into_inner().for_each(|((x, y), value)| {
+        let (source_x, source_y) = f(x as f32, y as f32);
+        let value: *mut S::Item = value;
+        unsafe { value.write(source.sample(source_x, source_y, interpolation, border)) };
+    });
+    image
+}
+
+/// Warp source image by an affine transform into a width x height image.
+/// The output pixel (x, y) is source sampled at (m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2]),
+/// so matrix maps destination coordinates back to source coordinates.
+/// Samples outside of valid area of source are resolved by border.
+pub fn warp_affine<S, W, H>(source: &S, matrix: &[[f32; 3]; 2], width: W, height: H, interpolation: Interpolation, border: &Border<S::Item>) -> PhysicalImage<S::Item, W, H>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let m = *matrix;
+    warp(source, width, height, interpolation, border, move |x, y| {
+        (m[0][0] * x + m[0][1] * y + m[0][2], m[1][0] * x + m[1][1] * y + m[1][2])
+    })
+}
+
+/// Warp source image by a perspective transform into a width x height image.
+/// The output pixel (x, y) is source sampled at (u / w, v / w) where (u, v, w) = matrix * (x, y, 1),
+/// so matrix maps destination coordinates back to source coordinates.
+/// Samples outside of valid area of source are resolved by border.
+pub fn warp_perspective<S, W, H>(source: &S, matrix: &[[f32; 3]; 3], width: W, height: H, interpolation: Interpolation, border: &Border<S::Item>) -> PhysicalImage<S::Item, W, H>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+{
+    let m = *matrix;
+    warp(source, width, height, interpolation, border, move |x, y| {
+        let w = m[2][0] * x + m[2][1] * y + m[2][2];
+        ((m[0][0] * x + m[0][1] * y + m[0][2]) / w, (m[1][0] * x + m[1][1] * y + m[1][2]) / w)
+    })
+}
+
+/// Get the affine matrix for warp_affine which rotates an image clockwise by angle (radians) around its center,
+/// and the output size. If expand is true, the output is large enough to hold the whole rotated image,
+/// otherwise it has the same size as the source.
+pub fn rotation_matrix(angle: f32, width: usize, height: usize, expand: bool) -> ([[f32; 3]; 2], usize, usize) {
+    let (sin, cos) = (angle as f64).sin_cos();
+    let (output_width, output_height) = if expand {
+        let fit = |a: f64, b: f64| (a * cos.abs() + b * sin.abs() - 1e-3).ceil().max(0.0) as usize;
+        (fit(width as f64, height as f64), fit(height as f64, width as f64))
+    } else {
+        (width, height)
+    };
+    let (center_x, center_y) = ((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0);
+    let (output_center_x, output_center_y) = ((output_width as f64 - 1.0) / 2.0, (output_height as f64 - 1.0) / 2.0);
+    let matrix = [
+        [cos as f32, sin as f32, (center_x - cos * output_center_x - sin * output_center_y) as f32],
+        [-sin as f32, cos as f32, (center_y + sin * output_center_x - cos * output_center_y) as f32],
+    ];
+    (matrix, output_width, output_height)
+}
+
+/// Rotate source image clockwise by angle (radians) around its center.
+/// If expand is true, the output is large enough to hold the whole rotated image, otherwise it has the same size as the source.
+/// Samples outside of valid area of source are resolved by border.
+pub fn rotate<S>(source: &S, angle: f32, expand: bool, interpolation: Interpolation, border: &Border<S::Item>) -> PhysicalImage<S::Item>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+{
+    let (matrix, width, height) = rotation_matrix(angle, source.width(), source.height(), expand);
+    warp_affine(source, &matrix, width, height, interpolation, border)
+}
+
+/// Remap source image by coordinate maps. The output pixel (x, y) is source sampled at (map_x(x, y), map_y(x, y)).
+/// Samples outside of valid area of source are resolved by border.
+/// # Panics
+/// Panics if sizes of map_x and map_y differ. If both sizes are constant, it is checked at compile time.
+pub fn remap<S, XW, XH, YW, YH>(
+    source: &S,
+    map_x: &PhysicalImage<f32, XW, XH>,
+    map_y: &PhysicalImage<f32, YW, YH>,
+    interpolation: Interpolation,
+    border: &Border<S::Item>,
+) -> PhysicalImage<S::Item, XW::ConstSide, XH::ConstSide>
+where
+    S: ReadPixel + Sync,
+    S::Item: Convolve,
+    XW: MayBeConst<usize> + Equals<YW>,
+    XH: MayBeConst<usize> + Equals<YH>,
+    YW: MayBeConst<usize>,
+    YH: MayBeConst<usize>,
+{
+    map_x.pix_iter().zip(map_y.pix_iter()).map(|(&x, &y)| source.sample(x, y, interpolation, border)).collect_image()
+}
+
+/// Build coordinate maps for remap whose value at (x, y) is f(x, y), evaluating f in parallel.
+pub fn maps_from_fn<W, H, F>(width: W, height: H, f: F) -> (PhysicalImage<f32, W, H>, PhysicalImage<f32, W, H>)
+where
+    W: MayBeConst<usize>,
+    H: MayBeConst<usize>,
+    F: Fn(usize, usize) -> (f32, f32) + Sync + Send,
+{
+    PhysicalImage::from_fn(width, height, f).into_pix_iter().unzip_images()
+}
+
+/// Brown-Conrady lens distortion model with camera intrinsics, as used by OpenCV.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrownConrady {
+    /// Focal length along x axis in pixels.
+    pub fx: f32,
+    /// Focal length along y axis in pixels.
+    pub fy: f32,
+    /// Principal point x in pixels.
+    pub cx: f32,
+    /// Principal point y in pixels.
+    pub cy: f32,
+    /// Radial distortion coefficient of r^2.
+    pub k1: f32,
+    /// Radial distortion coefficient of r^4.
+    pub k2: f32,
+    /// Radial distortion coefficient of r^6.
+    pub k3: f32,
+    /// First tangential distortion coefficient.
+    pub p1: f32,
+    /// Second tangential distortion coefficient.
+    pub p2: f32,
+}
+
+impl BrownConrady {
+    /// Map an undistorted pixel position to the position where the lens projects it.
+    pub fn distort(&self, x: f32, y: f32) -> (f32, f32) {
+        let (fx, fy, cx, cy) = (self.fx as f64, self.fy as f64, self.cx as f64, self.cy as f64);
+        let (k1, k2, k3, p1, p2) = (self.k1 as f64, self.k2 as f64, self.k3 as f64, self.p1 as f64, self.p2 as f64);
+        let (x, y) = ((x as f64 - cx) / fx, (y as f64 - cy) / fy);
+        let r2 = x * x + y * y;
+        let radial = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+        let distorted_x = x * radial + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x);
+        let distorted_y = y * radial + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y;
+        ((distorted_x * fx + cx) as f32, (distorted_y * fy + cy) as f32)
+    }
+
+    /// Build coordinate maps for remap which undistort a width x height image taken through this lens.
+    pub fn undistort_maps<W: MayBeConst<usize>, H: MayBeConst<usize>>(&self, width: W, height: H) -> (PhysicalImage<f32, W, H>, PhysicalImage<f32, W, H>) {
+        maps_from_fn(width, height, |x, y| self.distort(x as f32, y as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use partial_const::ConstUsize;
+
+    use crate::border::Border;
+    use crate::physical_image::PhysicalImage;
+    use crate::sample::Interpolation;
+    use crate::warp::{maps_from_fn, remap, rotate, rotation_matrix, warp_affine, warp_perspective, BrownConrady};
+    use crate::ReadPixel;
+
+    #[test]
+    fn warp() {
+        let image = PhysicalImage::from_fn(7, 5, |x, y| (x + y * 7) as f32);
+        let shifted = warp_affine(&image, &[[1.0, 0.0, 2.0], [0.0, 1.0, -1.0]], 4, 3, Interpolation::Bilinear, &Border::Constant(-1.0));
+        assert_eq!(
+            shifted.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            vec![-1.0, -1.0, -1.0, -1.0, 2.0, 3.0, 4.0, 5.0, 9.0, 10.0, 11.0, 12.0]
+        );
+        let scaled = warp_affine(
+            &image,
+            &[[0.5, 0.0, 0.0], [0.0, 0.5, 0.0]],
+            ConstUsize::<3>::new(),
+            ConstUsize::<2>::new(),
+            Interpolation::Bilinear,
+            &Border::Clamp,
+        );
+        assert_eq!(scaled.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![0.0, 0.5, 1.0, 3.5, 4.0, 4.5]);
+
+        let affine = [[0.8, -0.3, 1.5], [0.2, 1.1, 0.25]];
+        let perspective = [[1.6, -0.6, 3.0], [0.4, 2.2, 0.5], [0.0, 0.0, 2.0]];
+        for &interpolation in [Interpolation::Nearest, Interpolation::Bilinear, Interpolation::Bicubic].iter() {
+            let a = warp_affine(&image, &affine, 6, 6, interpolation, &Border::Reflect);
+            let b = warp_perspective(&image, &perspective, 6, 6, interpolation, &Border::Reflect);
+            for (a, b) in a.pix_iter_serialized().into_inner().zip(b.pix_iter_serialized().into_inner()) {
+                assert!((a - b).abs() < 1e-4);
+            }
+        }
+        let projected = warp_perspective(&image, &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.5, 0.0, 1.0]], 3, 1, Interpolation::Nearest, &Border::Clamp);
+        assert_eq!(projected.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(), vec![0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn rotation() {
+        let image = PhysicalImage::from_fn(7, 4, |x, y| (x * 3 + y * 50) as u16);
+        let rotated = rotate(&image, FRAC_PI_2, true, Interpolation::Nearest, &Border::Constant(0));
+        assert_eq!(
+            rotated.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            image.rotate90().pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        let rotated = rotate(&image, PI, false, Interpolation::Bilinear, &Border::Constant(0));
+        assert_eq!(
+            rotated.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            image.rotate180().pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        let rotated = rotate(&image, -FRAC_PI_2, true, Interpolation::Bicubic, &Border::Constant(0));
+        assert_eq!(
+            rotated.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            image.rotate270().pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+
+        let (_, width, height) = rotation_matrix(FRAC_PI_4, 10, 10, true);
+        assert_eq!((width, height), (15, 15));
+        let (_, width, height) = rotation_matrix(0.3, 40, 10, false);
+        assert_eq!((width, height), (40, 10));
+        let square = PhysicalImage::with_default(10, 10, 100u8);
+        let rotated = rotate(&square, FRAC_PI_4, true, Interpolation::Bilinear, &Border::Constant(0));
+        assert_eq!((rotated.width(), rotated.height()), (15, 15));
+        assert_eq!(rotated.get(7, 7), Some(&100));
+        assert_eq!(rotated.get(7, 1), Some(&100));
+        assert_eq!(rotated.get(0, 0), Some(&0));
+    }
+
+    #[test]
+    fn remap_maps() {
+        let image = PhysicalImage::from_fn(9, 6, |x, y| (x * 2 + y * 20) as f32);
+        let (map_x, map_y) = maps_from_fn(5, 4, |x, y| (x as f32 * 1.5 + 0.5, y as f32 + 1.0));
+        let remapped = remap(&image, &map_x, &map_y, Interpolation::Bilinear, &Border::Constant(0.0));
+        let expect = warp_affine(&image, &[[1.5, 0.0, 0.5], [0.0, 1.0, 1.0]], 5, 4, Interpolation::Bilinear, &Border::Constant(0.0));
+        assert_eq!(
+            remapped.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>(),
+            expect.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(remapped.get(1, 0), Some(&24.0));
+
+        let identity = BrownConrady {
+            fx: 100.0,
+            fy: 100.0,
+            cx: 4.0,
+            cy: 2.5,
+            k1: 0.0,
+            k2: 0.0,
+            k3: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+        };
+        let (map_x, map_y) = identity.undistort_maps(ConstUsize::<9>::new(), ConstUsize::<6>::new());
+        let remapped: PhysicalImage<f32, ConstUsize<9>, ConstUsize<6>> = remap(&image, &map_x, &map_y, Interpolation::Bicubic, &Border::Clamp);
+        for (a, b) in remapped
+            .pix_iter_serialized()
+            .into_inner()
+            .copied()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .zip(image.pix_iter_serialized().into_inner().copied().collect::<Vec<_>>())
+        {
+            assert!((a - b).abs() < 1e-3);
+        }
+
+        let lens = BrownConrady {
+            fx: 10.0,
+            fy: 20.0,
+            k1: 0.1,
+            k2: 0.01,
+            p1: 0.001,
+            p2: -0.002,
+            ..identity
+        };
+        assert_eq!(lens.distort(4.0, 2.5), (4.0, 2.5));
+        let (x, y) = lens.distort(14.0, 2.5);
+        assert!((x - (4.0 + 10.0 * (1.11 - 0.006))).abs() < 1e-4);
+        assert!((y - (2.5 + 20.0 * 0.001)).abs() < 1e-4);
+        let (map_x, map_y) = lens.undistort_maps(9, 6);
+        assert_eq!((map_x.get(8, 1), map_y.get(8, 1)), (Some(&lens.distort(8.0, 1.0).0), Some(&lens.distort(8.0, 1.0).1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "heights of zipped images differ")]
+    fn remap_size_mismatch() {
+        let image = PhysicalImage::<f32>::new(4, 4);
+        let (map_x, _) = maps_from_fn(3, 3, |x, y| (x as f32, y as f32));
+        let (_, map_y) = maps_from_fn(3, 2, |x, y| (x as f32, y as f32));
+        remap(&image, &map_x, &map_y, Interpolation::Nearest, &Border::Clamp);
+    }
+}